@@ -0,0 +1,82 @@
+// phidget-rs/examples/manager.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is an example application for the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+//! Rust Phidget example application to list the attached channels.
+//!
+
+use clap::{arg, ArgAction};
+use phidget::Manager;
+use std::{thread, time::Duration};
+
+// The package version is used as the app version
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// --------------------------------------------------------------------------
+
+fn main() -> anyhow::Result<()> {
+    let opts = clap::Command::new("manager")
+        .version(VERSION)
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about("Phidget Manager Example")
+        .disable_help_flag(true)
+        .arg(
+            arg!(--help "Print help information")
+                .short('?')
+                .action(ArgAction::Help),
+        )
+        .arg(arg!(-w --watch "Keep running and report channels as they come and go"))
+        .get_matches();
+
+    let mut mgr = Manager::new();
+
+    if opts.get_flag("watch") {
+        mgr.set_on_attach_handler(|_, info| {
+            println!("Attached: {:?}", info);
+        })?;
+        mgr.set_on_detach_handler(|_, info| {
+            println!("Detached: {:?}", info);
+        })?;
+        mgr.open()?;
+
+        // ^C handler wakes up the main thread
+        ctrlc::set_handler({
+            let thr = thread::current();
+            move || {
+                println!("\nExiting...");
+                thr.unpark();
+            }
+        })
+        .expect("Error setting Ctrl-C handler");
+
+        // Block until a ^C wakes us up to exit.
+        thread::park();
+    }
+    else {
+        mgr.open()?;
+
+        // Give the library a moment to report the connected channels
+        thread::sleep(Duration::from_millis(500));
+
+        for info in mgr.channels() {
+            println!(
+                "{} [{}] S/N: {}, Port: {}, Channel: {}, {:?}",
+                info.device_sku,
+                info.device_name,
+                info.serial_number,
+                info.hub_port,
+                info.channel,
+                info.channel_class
+            );
+        }
+    }
+    Ok(())
+}
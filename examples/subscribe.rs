@@ -0,0 +1,54 @@
+// phidget-rs/examples/subscribe.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is an example application for the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+//! Rust Phidget example application to receive the events from multiple
+//! devices in a single thread.
+//!
+//! This opens a temperature and humidity sensor, like the HUM1001, and
+//! waits on the events from both of them.
+//!
+
+use crossbeam_channel::select;
+use phidget::{
+    devices::{HumiditySensor, TemperatureSensor},
+    EventKind, Phidget,
+};
+use std::time::Duration;
+
+// The open/connect timeout
+const TIMEOUT: Duration = phidget::TIMEOUT_DEFAULT;
+
+// --------------------------------------------------------------------------
+
+fn main() -> anyhow::Result<()> {
+    let mut temp_sensor = TemperatureSensor::new();
+    let temp_rx = temp_sensor.subscribe()?;
+
+    let mut hum_sensor = HumiditySensor::new();
+    let hum_rx = hum_sensor.subscribe()?;
+
+    temp_sensor.open_wait(TIMEOUT)?;
+    hum_sensor.open_wait(TIMEOUT)?;
+
+    loop {
+        select! {
+            recv(temp_rx) -> evt => match evt?.kind {
+                EventKind::Change(t) => println!("Temperature: {}", t),
+                kind => println!("Temperature sensor: {:?}", kind),
+            },
+            recv(hum_rx) -> evt => match evt?.kind {
+                EventKind::Change(h) => println!("Humidity: {}", h),
+                kind => println!("Humidity sensor: {:?}", kind),
+            },
+        }
+    }
+}
@@ -78,6 +78,10 @@ fn main() -> anyhow::Result<()> {
         println!("Temperature: {}", t);
     })?;
 
+    sensor.set_on_error_handler(|_, code, descr| {
+        eprintln!("Error: {:?}: {}", code, descr);
+    })?;
+
     // ^C handler wakes up the main thread
     ctrlc::set_handler({
         let thr = thread::current();
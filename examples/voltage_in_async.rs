@@ -0,0 +1,90 @@
+// phidget-rs/examples/voltage_in_async.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is an example application for the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+//! Rust Phidget example application to read voltage input values
+//! asynchronously.
+//!
+//! This requires the `async` feature:
+//!
+//! ```text
+//! $ cargo run --example voltage_in_async --features async -- -n 20
+//! ```
+
+use clap::{arg, value_parser, ArgAction};
+use futures::{executor::block_on, StreamExt};
+use phidget::{devices::VoltageInput, Phidget};
+
+// The package version is used as the app version
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// --------------------------------------------------------------------------
+
+fn main() -> anyhow::Result<()> {
+    let opts = clap::Command::new("voltage_in_async")
+        .version(VERSION)
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about("Phidget Asynchronous Voltage (Analog) Input Example")
+        .disable_help_flag(true)
+        .arg(
+            arg!(--help "Print help information")
+                .short('?')
+                .action(ArgAction::Help),
+        )
+        .arg(
+            arg!(-s --serial [serial_num] "Specify the serial number of the device to open")
+                .value_parser(value_parser!(i32)),
+        )
+        .arg(
+            arg!(-c --channel [chan] "Specify the channel number of the device to open")
+                .value_parser(value_parser!(i32)),
+        )
+        .arg(
+            arg!(-p --port [port] "Use a specific port on a VINT hub directly")
+                .value_parser(value_parser!(i32)),
+        )
+        .arg(arg!(-h --hub "Use a hub VINT input port directly").action(ArgAction::SetTrue))
+        .arg(
+            arg!(-n --num [num] "The number of readings to take")
+                .default_value("10")
+                .value_parser(value_parser!(usize)),
+        )
+        .get_matches();
+
+    let n = *opts.get_one::<usize>("num").unwrap();
+
+    println!("Opening Phidget voltage input device...");
+    let mut vin = VoltageInput::new();
+
+    vin.set_is_hub_port_device(opts.get_flag("hub"))?;
+    if let Some(&port) = opts.get_one::<i32>("port") {
+        vin.set_hub_port(port)?;
+    }
+
+    if let Some(&num) = opts.get_one::<i32>("serial") {
+        vin.set_serial_number(num)?;
+    }
+
+    if let Some(&chan) = opts.get_one::<i32>("channel") {
+        vin.set_channel(chan)?;
+    }
+
+    block_on(async {
+        vin.open_attached().await?;
+        println!("Opened on hub port: {}", vin.hub_port()?);
+
+        let mut voltages = vin.voltage_changes()?.take(n);
+        while let Some(v) = voltages.next().await {
+            println!("{:.4}", v);
+        }
+        Ok(())
+    })
+}
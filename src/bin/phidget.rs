@@ -1,13 +1,154 @@
-use phidget::{
-    devices::{HumiditySensor, TemperatureSensor},
-    Phidget,
-};
+use clap::{arg, value_parser, ArgMatches};
+use phidget::{devices::*, ChannelSelector, Phidget};
 
 use std::{thread, time::Duration};
 
 const TIMEOUT: Duration = Duration::from_millis(5000);
 
+// The package version is used as the app version
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// A class of channel that can be opened to write a label
+struct ChannelType {
+    // The command-line name of the class, like "voltage-input"
+    name: String,
+    // Creates a channel object of the class
+    new: fn() -> Box<dyn Phidget>,
+}
+
+impl ChannelType {
+    // Creates the entry for a device type, naming it from the type in
+    // kebab case, so `VoltageInput` is "voltage-input".
+    fn of<P: Phidget + Default + 'static>() -> Self {
+        let ty = std::any::type_name::<P>();
+        let ty = ty.rsplit("::").next().unwrap_or(ty);
+        let mut name = String::new();
+        for c in ty.chars() {
+            if c.is_ascii_uppercase() && !name.is_empty() {
+                name.push('-');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        Self {
+            name,
+            new: || Box::<P>::default(),
+        }
+    }
+}
+
+// The classes of channel that can be opened to write a label
+fn channel_types() -> Vec<ChannelType> {
+    vec![
+        ChannelType::of::<Accelerometer>(),
+        ChannelType::of::<BldcMotor>(),
+        ChannelType::of::<CapacitiveTouch>(),
+        ChannelType::of::<CurrentInput>(),
+        ChannelType::of::<DcMotor>(),
+        ChannelType::of::<DigitalInput>(),
+        ChannelType::of::<DigitalOutput>(),
+        ChannelType::of::<DistanceSensor>(),
+        ChannelType::of::<Encoder>(),
+        ChannelType::of::<FrequencyCounter>(),
+        ChannelType::of::<Gps>(),
+        ChannelType::of::<Gyroscope>(),
+        ChannelType::of::<Hub>(),
+        ChannelType::of::<HumiditySensor>(),
+        ChannelType::of::<Ir>(),
+        ChannelType::of::<Lcd>(),
+        ChannelType::of::<LightSensor>(),
+        ChannelType::of::<Magnetometer>(),
+        ChannelType::of::<MotorPositionController>(),
+        ChannelType::of::<PhSensor>(),
+        ChannelType::of::<PowerGuard>(),
+        ChannelType::of::<PressureSensor>(),
+        ChannelType::of::<RcServo>(),
+        ChannelType::of::<ResistanceInput>(),
+        ChannelType::of::<Rfid>(),
+        ChannelType::of::<SoundSensor>(),
+        ChannelType::of::<Spatial>(),
+        ChannelType::of::<Stepper>(),
+        ChannelType::of::<TemperatureSensor>(),
+        ChannelType::of::<VoltageInput>(),
+        ChannelType::of::<VoltageOutput>(),
+        ChannelType::of::<VoltageRatioInput>(),
+    ]
+}
+
 fn main() -> anyhow::Result<()> {
+    let types = channel_types();
+
+    let opts = clap::Command::new("phidget")
+        .version(VERSION)
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about("Phidget utility")
+        .subcommand(
+            clap::Command::new("label")
+                .about("Write a persistent label to the flash memory of a device")
+                .arg(
+                    arg!(-c --class [class] "The class of a channel on the device")
+                        .possible_values(types.iter().map(|t| t.name.as_str()))
+                        .default_value("hub"),
+                )
+                .arg(
+                    arg!(-s --serial [serial_num] "The serial number of the device")
+                        .value_parser(value_parser!(i32)),
+                )
+                .arg(
+                    arg!(-p --port [port] "The VINT Hub port of the device")
+                        .value_parser(value_parser!(i32)),
+                )
+                .arg(
+                    arg!(-n --channel [channel] "The index of the channel on the device")
+                        .value_parser(value_parser!(i32)),
+                )
+                .arg(arg!(<label> "The label to write to the device")),
+        )
+        .get_matches();
+
+    match opts.subcommand() {
+        Some(("label", args)) => write_label(&types, args),
+        _ => monitor(),
+    }
+}
+
+// Writes a label to a device, so that its channels can be opened
+// by label rather than serial number. The label is written through
+// any channel of the device, as picked out by the command-line filters.
+fn write_label(types: &[ChannelType], args: &ArgMatches) -> anyhow::Result<()> {
+    let class = args.get_one::<String>("class").unwrap();
+    let label = args.get_one::<String>("label").unwrap();
+
+    let mut sel = ChannelSelector::any();
+    if let Some(&sn) = args.get_one::<i32>("serial") {
+        sel = ChannelSelector::serial_number(sn);
+    }
+    if let Some(&port) = args.get_one::<i32>("port") {
+        sel = sel.port(port);
+    }
+    if let Some(&chan) = args.get_one::<i32>("channel") {
+        sel = sel.channel(chan);
+    }
+
+    let ty = types
+        .iter()
+        .find(|t| &t.name == class)
+        .ok_or_else(|| anyhow::anyhow!("Unknown channel class '{}'", class))?;
+
+    let mut ph = (ty.new)();
+    sel.apply(ph.as_mut())?;
+    ph.open_wait(TIMEOUT)?;
+
+    ph.write_device_label(label)?;
+    println!(
+        "Wrote label '{}' to device {}",
+        ph.device_label()?,
+        ph.serial_number()?
+    );
+    Ok(())
+}
+
+// Monitors a humidity and temperature sensor until ^C is pressed.
+fn monitor() -> anyhow::Result<()> {
     println!("{}", phidget::library_version()?);
     println!("{}", phidget::library_version_number()?);
 
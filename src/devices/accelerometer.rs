@@ -0,0 +1,352 @@
+// phidget-rs/src/accelerometer.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetAccelerometerHandle as AccelerometerHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust acceleration change callback.
+pub type AccelerationChangeCallback = dyn Fn(&Accelerometer, [f64; 3], f64) + Send + 'static;
+
+/// An acceleration reading from an accelerometer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccelerationChange {
+    /// The acceleration for each axis, in g
+    pub acceleration: [f64; 3],
+    /// The timestamp of the reading, in milliseconds
+    pub timestamp: f64,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget accelerometer
+pub struct Accelerometer {
+    // Handle to the channel in the phidget22 library
+    chan: AccelerometerHandle,
+    // Double-boxed AccelerationChangeCallback, if registered
+    acceleration_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Accelerometer {
+    /// Create a new accelerometer.
+    pub fn new() -> Self {
+        let mut chan: AccelerometerHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetAccelerometer_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &AccelerometerHandle {
+        &self.chan
+    }
+
+    /// Gets the acceleration for each axis, in g.
+    pub fn acceleration(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum acceleration for each axis, in g.
+    pub fn min_acceleration(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration for each axis, in g.
+    pub fn max_acceleration(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the acceleration change trigger.
+    pub fn acceleration_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getAccelerationChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in acceleration, in g, that will fire
+    /// an acceleration change event.
+    pub fn set_acceleration_change_trigger(&self, acceleration_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_setAccelerationChangeTrigger(
+                self.chan,
+                acceleration_change_trigger,
+            )
+        })
+    }
+
+    /// Gets the minimum acceleration change trigger.
+    pub fn min_acceleration_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getMinAccelerationChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration change trigger.
+    pub fn max_acceleration_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getMaxAccelerationChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the number of axes the accelerometer can measure.
+    pub fn axis_count(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getAxisCount(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines whether the internal heater is enabled.
+    pub fn heating_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getHeatingEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the internal heater, which keeps the sensor
+    /// at a stable temperature.
+    pub fn set_heating_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_setHeatingEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Gets the timestamp of the most recent reading, in milliseconds.
+    pub fn timestamp(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetAccelerometer_getTimestamp(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for acceleration change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_acceleration_change(
+        chan: AccelerometerHandle,
+        ctx: *mut c_void,
+        acceleration: *const f64,
+        timestamp: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<AccelerationChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, *(acceleration as *const [f64; 3]), timestamp);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive acceleration change callbacks.
+    pub fn set_on_acceleration_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Accelerometer, [f64; 3], f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<AccelerationChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetAccelerometer_setOnAccelerationChangeHandler(
+                self.chan,
+                Some(Self::on_acceleration_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<AccelerationChangeCallback>(
+            &mut self.acceleration_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of acceleration change events.
+    ///
+    /// This replaces any acceleration change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn acceleration_changes(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, AccelerationChange>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_acceleration_change_handler(move |_, acceleration, timestamp| {
+            tx.send(AccelerationChange {
+                acceleration,
+                timestamp,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetAccelerometer_setOnAccelerationChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<AccelerationChangeCallback>(self.acceleration_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any acceleration change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<AccelerationChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_acceleration_change_handler({
+            let tx = tx.clone();
+            move |_, acceleration, timestamp| {
+                let _ = tx.send(Event::new(EventKind::Change(AccelerationChange {
+                    acceleration,
+                    timestamp,
+                })));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Accelerometer {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Accelerometer {}
+
+impl Default for Accelerometer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<AccelerometerHandle> for Accelerometer {
+    fn from(chan: AccelerometerHandle) -> Self {
+        Self {
+            chan,
+            acceleration_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Accelerometer {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetAccelerometer_delete(&mut self.chan);
+            crate::drop_cb::<AccelerationChangeCallback>(self.acceleration_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
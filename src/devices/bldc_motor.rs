@@ -0,0 +1,636 @@
+// phidget-rs/src/bldc_motor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetBLDCMotorHandle as BldcMotorHandle, PhidgetHandle};
+use std::{mem, os::raw::c_void, ptr};
+
+/// The function type for the safe Rust velocity update callback.
+pub type VelocityUpdateCallback = dyn Fn(&BldcMotor, f64) + Send + 'static;
+/// The function type for the safe Rust position change callback.
+pub type PositionChangeCallback = dyn Fn(&BldcMotor, f64) + Send + 'static;
+/// The function type for the safe Rust braking strength change callback.
+pub type BrakingStrengthChangeCallback = dyn Fn(&BldcMotor, f64) + Send + 'static;
+
+/// The value changes reported by a BLDC motor controller through `BldcMotor::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BldcMotorChange {
+    /// Velocity update
+    Velocity(f64),
+    /// Position change
+    Position(f64),
+    /// Braking strength change
+    BrakingStrength(f64),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget brushless DC motor controller
+pub struct BldcMotor {
+    // Handle to the channel in the phidget22 library
+    chan: BldcMotorHandle,
+    // Double-boxed VelocityUpdateCallback, if registered
+    velocity_update_cb: Option<*mut c_void>,
+    // Double-boxed PositionChangeCallback, if registered
+    position_change_cb: Option<*mut c_void>,
+    // Double-boxed BrakingStrengthChangeCallback, if registered
+    braking_strength_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl BldcMotor {
+    /// Create a new brushless DC motor controller.
+    pub fn new() -> Self {
+        let mut chan: BldcMotorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetBLDCMotor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &BldcMotorHandle {
+        &self.chan
+    }
+
+    /// Enables the failsafe feature for the channel, with the specified
+    /// failsafe time, in milliseconds.
+    pub fn set_enable_failsafe(&self, failsafe_time: u32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_enableFailsafe(self.chan, failsafe_time)
+        })
+    }
+
+    /// Resets the failsafe timer, if one has been set.
+    pub fn set_reset_failsafe(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_resetFailsafe(self.chan) })
+    }
+
+    /// Gets the minimum failsafe time, in milliseconds.
+    pub fn min_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMinFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum failsafe time, in milliseconds.
+    pub fn max_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMaxFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the acceleration.
+    pub fn acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the acceleration.
+    pub fn set_acceleration(&self, acceleration: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setAcceleration(self.chan, acceleration)
+        })
+    }
+
+    /// Gets the minimum acceleration.
+    pub fn min_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration.
+    pub fn max_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Adds an offset, in rescaled units, to the position. This is useful
+    /// for zeroing the position.
+    pub fn add_position_offset(&self, offset: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_addPositionOffset(self.chan, offset) })
+    }
+
+    /// Gets the current limit, in amps.
+    pub fn current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the current limit, in amps.
+    pub fn set_current_limit(&self, current_limit: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setCurrentLimit(self.chan, current_limit)
+        })
+    }
+
+    /// Gets the minimum current limit, in amps.
+    pub fn min_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMinCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current limit, in amps.
+    pub fn max_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMaxCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the position of the motor, in rescaled units.
+    pub fn position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum position.
+    pub fn min_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getMinPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum position.
+    pub fn max_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getMaxPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the rescale factor.
+    pub fn rescale_factor(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getRescaleFactor(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the rescale factor, which converts the position from
+    /// commutation steps into user units.
+    pub fn set_rescale_factor(&self, rescale_factor: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setRescaleFactor(self.chan, rescale_factor)
+        })
+    }
+
+    /// Gets the stall velocity.
+    pub fn stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the velocity, in rescaled units per second, below which the
+    /// motor is considered stalled. Zero disables stall protection.
+    pub fn set_stall_velocity(&self, stall_velocity: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setStallVelocity(self.chan, stall_velocity)
+        })
+    }
+
+    /// Gets the minimum stall velocity.
+    pub fn min_stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMinStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum stall velocity.
+    pub fn max_stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMaxStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the current braking strength being applied to the motor.
+    pub fn braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum braking strength.
+    pub fn min_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMinBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum braking strength.
+    pub fn max_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getMaxBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the braking strength applied when the motor is not moving.
+    pub fn target_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getTargetBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the braking strength applied when the target velocity is zero.
+    pub fn set_target_braking_strength(&self, braking_strength: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setTargetBrakingStrength(self.chan, braking_strength)
+        })
+    }
+
+    /// Gets the target velocity.
+    pub fn target_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_getTargetVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the velocity the motor should reach, as a duty cycle from -1.0 to 1.0.
+    /// The sign of the value determines the direction.
+    pub fn set_target_velocity(&self, target_velocity: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetBLDCMotor_setTargetVelocity(self.chan, target_velocity)
+        })
+    }
+
+    /// Gets the current velocity of the motor, as a duty cycle.
+    pub fn velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum velocity.
+    pub fn min_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getMinVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum velocity.
+    pub fn max_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetBLDCMotor_getMaxVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for velocity update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_velocity_update(
+        chan: BldcMotorHandle,
+        ctx: *mut c_void,
+        velocity: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<VelocityUpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, velocity);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive velocity update callbacks.
+    pub fn set_on_velocity_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&BldcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<VelocityUpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetBLDCMotor_setOnVelocityUpdateHandler(
+                self.chan,
+                Some(Self::on_velocity_update),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<VelocityUpdateCallback>(&mut self.velocity_update_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for position change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_change(
+        chan: BldcMotorHandle,
+        ctx: *mut c_void,
+        position: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position change callbacks.
+    pub fn set_on_position_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&BldcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetBLDCMotor_setOnPositionChangeHandler(
+                self.chan,
+                Some(Self::on_position_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for braking strength change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_braking_strength_change(
+        chan: BldcMotorHandle,
+        ctx: *mut c_void,
+        braking_strength: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<BrakingStrengthChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, braking_strength);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive braking strength change callbacks.
+    pub fn set_on_braking_strength_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&BldcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<BrakingStrengthChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetBLDCMotor_setOnBrakingStrengthChangeHandler(
+                self.chan,
+                Some(Self::on_braking_strength_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<BrakingStrengthChangeCallback>(
+            &mut self.braking_strength_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of velocity update events.
+    ///
+    /// This replaces any velocity update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn velocity_updates(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_velocity_update_handler(move |_, velocity| tx.send(velocity))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetBLDCMotor_setOnVelocityUpdateHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<VelocityUpdateCallback>(self.velocity_update_cb.take());
+        }))
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(move |_, position| tx.send(position))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetBLDCMotor_setOnPositionChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of braking strength change events.
+    ///
+    /// This replaces any braking strength change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn braking_strength_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_braking_strength_change_handler(move |_, braking_strength| {
+            tx.send(braking_strength)
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetBLDCMotor_setOnBrakingStrengthChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<BrakingStrengthChangeCallback>(self.braking_strength_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any velocity update, position change, braking strength change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<BldcMotorChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_velocity_update_handler({
+            let tx = tx.clone();
+            move |_, velocity| {
+                let _ = tx.send(Event::new(EventKind::Change(BldcMotorChange::Velocity(
+                    velocity,
+                ))));
+            }
+        })?;
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, position| {
+                let _ = tx.send(Event::new(EventKind::Change(BldcMotorChange::Position(
+                    position,
+                ))));
+            }
+        })?;
+        self.set_on_braking_strength_change_handler({
+            let tx = tx.clone();
+            move |_, braking_strength| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    BldcMotorChange::BrakingStrength(braking_strength),
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for BldcMotor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for BldcMotor {}
+
+impl Default for BldcMotor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<BldcMotorHandle> for BldcMotor {
+    fn from(chan: BldcMotorHandle) -> Self {
+        Self {
+            chan,
+            velocity_update_cb: None,
+            position_change_cb: None,
+            braking_strength_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for BldcMotor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetBLDCMotor_delete(&mut self.chan);
+            crate::drop_cb::<VelocityUpdateCallback>(self.velocity_update_cb.take());
+            crate::drop_cb::<PositionChangeCallback>(self.position_change_cb.take());
+            crate::drop_cb::<BrakingStrengthChangeCallback>(self.braking_strength_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,398 @@
+// phidget-rs/src/capacitive_touch.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetCapacitiveTouchHandle as CapacitiveTouchHandle, PhidgetHandle,
+};
+use std::{mem, os::raw::c_void, ptr};
+
+/// The function type for the safe Rust touch callback.
+pub type TouchCallback = dyn Fn(&CapacitiveTouch, f64) + Send + 'static;
+/// The function type for the safe Rust touch end callback.
+pub type TouchEndCallback = dyn Fn(&CapacitiveTouch) + Send + 'static;
+
+/// The value changes reported by a capacitive touch sensor through `CapacitiveTouch::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapacitiveTouchChange {
+    /// Touch
+    Touch(f64),
+    /// Touch end
+    TouchEnd,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget capacitive touch sensor
+pub struct CapacitiveTouch {
+    // Handle to the channel in the phidget22 library
+    chan: CapacitiveTouchHandle,
+    // Double-boxed TouchCallback, if registered
+    touch_cb: Option<*mut c_void>,
+    // Double-boxed TouchEndCallback, if registered
+    touch_end_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl CapacitiveTouch {
+    /// Create a new capacitive touch sensor.
+    pub fn new() -> Self {
+        let mut chan: CapacitiveTouchHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetCapacitiveTouch_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &CapacitiveTouchHandle {
+        &self.chan
+    }
+
+    /// Determines if the sensor is currently being touched.
+    pub fn is_touched(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getIsTouched(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Gets the most recent touch value, from 0.0 to 1.0. A higher value
+    /// indicates a stronger touch.
+    pub fn touch_value(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getTouchValue(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum touch value.
+    pub fn min_touch_value(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMinTouchValue(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum touch value.
+    pub fn max_touch_value(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMaxTouchValue(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the sensitivity.
+    pub fn sensitivity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getSensitivity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the sensitivity of the sensor. A higher value makes it easier
+    /// to trigger a touch.
+    pub fn set_sensitivity(&self, sensitivity: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_setSensitivity(self.chan, sensitivity)
+        })
+    }
+
+    /// Gets the minimum sensitivity.
+    pub fn min_sensitivity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMinSensitivity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum sensitivity.
+    pub fn max_sensitivity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMaxSensitivity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the touch value change trigger.
+    pub fn touch_value_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getTouchValueChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in touch value that will fire a touch event.
+    pub fn set_touch_value_change_trigger(&self, touch_value_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_setTouchValueChangeTrigger(
+                self.chan,
+                touch_value_change_trigger,
+            )
+        })
+    }
+
+    /// Gets the minimum touch value change trigger.
+    pub fn min_touch_value_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMinTouchValueChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum touch value change trigger.
+    pub fn max_touch_value_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCapacitiveTouch_getMaxTouchValueChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for touch events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_touch(chan: CapacitiveTouchHandle, ctx: *mut c_void, touch_value: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<TouchCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, touch_value);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive touch callbacks.
+    pub fn set_on_touch_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&CapacitiveTouch, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<TouchCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetCapacitiveTouch_setOnTouchHandler(self.chan, Some(Self::on_touch), ctx)
+        };
+        self.retired_cbs
+            .update::<TouchCallback>(&mut self.touch_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for touch end events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_touch_end(chan: CapacitiveTouchHandle, ctx: *mut c_void) {
+        if !ctx.is_null() {
+            let cb: &mut Box<TouchEndCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive touch end callbacks.
+    pub fn set_on_touch_end_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&CapacitiveTouch) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<TouchEndCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetCapacitiveTouch_setOnTouchEndHandler(
+                self.chan,
+                Some(Self::on_touch_end),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<TouchEndCallback>(&mut self.touch_end_cb, ctx, rc)
+    }
+
+    /// Gets a stream of touch events.
+    ///
+    /// This replaces any touch handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn touches(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_touch_handler(move |_, touch_value| tx.send(touch_value))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetCapacitiveTouch_setOnTouchHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<TouchCallback>(self.touch_cb.take());
+        }))
+    }
+
+    /// Gets a stream of touch end events.
+    ///
+    /// This replaces any touch end handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn touch_ends(&mut self) -> Result<crate::stream::EventStream<'_, ()>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_touch_end_handler(move |_| tx.send(()))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetCapacitiveTouch_setOnTouchEndHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<TouchEndCallback>(self.touch_end_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any touch, touch end, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<CapacitiveTouchChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_touch_handler({
+            let tx = tx.clone();
+            move |_, touch_value| {
+                let _ = tx.send(Event::new(EventKind::Change(CapacitiveTouchChange::Touch(
+                    touch_value,
+                ))));
+            }
+        })?;
+        self.set_on_touch_end_handler({
+            let tx = tx.clone();
+            move |_| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    CapacitiveTouchChange::TouchEnd,
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for CapacitiveTouch {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for CapacitiveTouch {}
+
+impl Default for CapacitiveTouch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<CapacitiveTouchHandle> for CapacitiveTouch {
+    fn from(chan: CapacitiveTouchHandle) -> Self {
+        Self {
+            chan,
+            touch_cb: None,
+            touch_end_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for CapacitiveTouch {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetCapacitiveTouch_delete(&mut self.chan);
+            crate::drop_cb::<TouchCallback>(self.touch_cb.take());
+            crate::drop_cb::<TouchEndCallback>(self.touch_end_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
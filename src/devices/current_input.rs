@@ -0,0 +1,297 @@
+// phidget-rs/src/current_input.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    devices::digital_input::PowerSupply,
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetCurrentInputHandle as CurrentInputHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust current change callback.
+pub type CurrentChangeCallback = dyn Fn(&CurrentInput, f64) + Send + 'static;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget current input
+pub struct CurrentInput {
+    // Handle to the channel in the phidget22 library
+    chan: CurrentInputHandle,
+    // Double-boxed CurrentChangeCallback, if registered
+    current_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl CurrentInput {
+    /// Create a new current input.
+    pub fn new() -> Self {
+        let mut chan: CurrentInputHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetCurrentInput_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &CurrentInputHandle {
+        &self.chan
+    }
+
+    /// Gets the measured current, in amps.
+    pub fn current(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetCurrentInput_getCurrent(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum current that can be measured, in amps.
+    pub fn min_current(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getMinCurrent(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current that can be measured, in amps.
+    pub fn max_current(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getMaxCurrent(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the current change trigger.
+    pub fn current_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getCurrentChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in current, in amps, that will fire
+    /// a current change event.
+    pub fn set_current_change_trigger(&self, current_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_setCurrentChangeTrigger(self.chan, current_change_trigger)
+        })
+    }
+
+    /// Gets the minimum current change trigger.
+    pub fn min_current_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getMinCurrentChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current change trigger.
+    pub fn max_current_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getMaxCurrentChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the power supply voltage for the sensor.
+    pub fn power_supply(&self) -> Result<PowerSupply> {
+        let mut value: ffi::Phidget_PowerSupply = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_getPowerSupply(self.chan, &mut value)
+        })?;
+        PowerSupply::try_from(value)
+    }
+
+    /// Sets the power supply voltage for the sensor.
+    pub fn set_power_supply(&self, power_supply: PowerSupply) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetCurrentInput_setPowerSupply(self.chan, power_supply as c_uint)
+        })
+    }
+
+    // Low-level, unsafe, callback for current change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_current_change(
+        chan: CurrentInputHandle,
+        ctx: *mut c_void,
+        current: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<CurrentChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, current);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive current change callbacks.
+    pub fn set_on_current_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&CurrentInput, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<CurrentChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetCurrentInput_setOnCurrentChangeHandler(
+                self.chan,
+                Some(Self::on_current_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<CurrentChangeCallback>(&mut self.current_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of current change events.
+    ///
+    /// This replaces any current change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn current_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_current_change_handler(move |_, current| tx.send(current))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetCurrentInput_setOnCurrentChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<CurrentChangeCallback>(self.current_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any current change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_current_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for CurrentInput {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for CurrentInput {}
+
+impl Default for CurrentInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<CurrentInputHandle> for CurrentInput {
+    fn from(chan: CurrentInputHandle) -> Self {
+        Self {
+            chan,
+            current_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for CurrentInput {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetCurrentInput_delete(&mut self.chan);
+            crate::drop_cb::<CurrentChangeCallback>(self.current_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,635 @@
+// phidget-rs/src/dc_motor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetDCMotorHandle as DcMotorHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust velocity update callback.
+pub type VelocityUpdateCallback = dyn Fn(&DcMotor, f64) + Send + 'static;
+/// The function type for the safe Rust braking strength change callback.
+pub type BrakingStrengthChangeCallback = dyn Fn(&DcMotor, f64) + Send + 'static;
+/// The function type for the safe Rust back-EMF change callback.
+pub type BackEmfChangeCallback = dyn Fn(&DcMotor, f64) + Send + 'static;
+
+/// The value changes reported by a DC motor controller through `DcMotor::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DcMotorChange {
+    /// Velocity update
+    Velocity(f64),
+    /// Braking strength change
+    BrakingStrength(f64),
+    /// Back-EMF change
+    BackEmf(f64),
+}
+
+/// The mode of the cooling fan on a motor controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum FanMode {
+    /// The fan is off
+    Off = ffi::Phidget_FanMode_FAN_MODE_OFF,
+    /// The fan is on
+    On = ffi::Phidget_FanMode_FAN_MODE_ON,
+    /// The fan turns on automatically when the controller gets hot
+    Auto = ffi::Phidget_FanMode_FAN_MODE_AUTO,
+}
+
+impl TryFrom<u32> for FanMode {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use FanMode::*;
+        match val {
+            ffi::Phidget_FanMode_FAN_MODE_OFF => Ok(Off),
+            ffi::Phidget_FanMode_FAN_MODE_ON => Ok(On),
+            ffi::Phidget_FanMode_FAN_MODE_AUTO => Ok(Auto),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget DC motor controller
+pub struct DcMotor {
+    // Handle to the channel in the phidget22 library
+    chan: DcMotorHandle,
+    // Double-boxed VelocityUpdateCallback, if registered
+    velocity_update_cb: Option<*mut c_void>,
+    // Double-boxed BrakingStrengthChangeCallback, if registered
+    braking_strength_change_cb: Option<*mut c_void>,
+    // Double-boxed BackEmfChangeCallback, if registered
+    back_emf_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl DcMotor {
+    /// Create a new DC motor controller.
+    pub fn new() -> Self {
+        let mut chan: DcMotorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetDCMotor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &DcMotorHandle {
+        &self.chan
+    }
+
+    /// Enables the failsafe feature for the channel, with the specified
+    /// failsafe time, in milliseconds.
+    pub fn set_enable_failsafe(&self, failsafe_time: u32) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_enableFailsafe(self.chan, failsafe_time) })
+    }
+
+    /// Resets the failsafe timer, if one has been set.
+    pub fn set_reset_failsafe(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_resetFailsafe(self.chan) })
+    }
+
+    /// Gets the minimum failsafe time, in milliseconds.
+    pub fn min_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMinFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum failsafe time, in milliseconds.
+    pub fn max_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMaxFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the acceleration.
+    pub fn acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getAcceleration(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the acceleration.
+    pub fn set_acceleration(&self, acceleration: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_setAcceleration(self.chan, acceleration) })
+    }
+
+    /// Gets the minimum acceleration.
+    pub fn min_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration.
+    pub fn max_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the most recent back-EMF value, in volts.
+    /// Back-EMF sensing must be enabled to read the value.
+    pub fn back_emf(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getBackEMF(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Determines whether back-EMF sensing is enabled.
+    pub fn back_emf_sensing_state(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getBackEMFSensingState(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables back-EMF sensing.
+    pub fn set_back_emf_sensing_state(&self, on: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_setBackEMFSensingState(self.chan, c_int::from(on))
+        })
+    }
+
+    /// Gets the current braking strength being applied to the motor.
+    pub fn braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum braking strength.
+    pub fn min_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMinBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum braking strength.
+    pub fn max_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMaxBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the braking strength applied when the motor is not moving.
+    pub fn target_braking_strength(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getTargetBrakingStrength(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the braking strength applied when the target velocity is zero.
+    pub fn set_target_braking_strength(&self, braking_strength: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_setTargetBrakingStrength(self.chan, braking_strength)
+        })
+    }
+
+    /// Gets the current limit, in amps.
+    pub fn current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getCurrentLimit(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the current limit, in amps.
+    pub fn set_current_limit(&self, current_limit: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_setCurrentLimit(self.chan, current_limit) })
+    }
+
+    /// Gets the minimum current limit, in amps.
+    pub fn min_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMinCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current limit, in amps.
+    pub fn max_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMaxCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the current regulator gain.
+    pub fn current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the current regulator gain.
+    pub fn set_current_regulator_gain(&self, current_regulator_gain: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_setCurrentRegulatorGain(self.chan, current_regulator_gain)
+        })
+    }
+
+    /// Gets the minimum current regulator gain.
+    pub fn min_current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMinCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current regulator gain.
+    pub fn max_current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getMaxCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the fan mode.
+    pub fn fan_mode(&self) -> Result<FanMode> {
+        let mut value: ffi::Phidget_FanMode = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getFanMode(self.chan, &mut value) })?;
+        FanMode::try_from(value)
+    }
+
+    /// Sets the fan mode.
+    pub fn set_fan_mode(&self, fan_mode: FanMode) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_setFanMode(self.chan, fan_mode as c_uint) })
+    }
+
+    /// Gets the target velocity.
+    pub fn target_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_getTargetVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the velocity the motor should reach, as a duty cycle from -1.0 to 1.0.
+    /// The sign of the value determines the direction.
+    pub fn set_target_velocity(&self, target_velocity: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDCMotor_setTargetVelocity(self.chan, target_velocity)
+        })
+    }
+
+    /// Gets the current velocity of the motor, as a duty cycle.
+    pub fn velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum velocity.
+    pub fn min_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getMinVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum velocity.
+    pub fn max_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetDCMotor_getMaxVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for velocity update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_velocity_update(chan: DcMotorHandle, ctx: *mut c_void, velocity: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<VelocityUpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, velocity);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive velocity update callbacks.
+    pub fn set_on_velocity_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&DcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<VelocityUpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDCMotor_setOnVelocityUpdateHandler(
+                self.chan,
+                Some(Self::on_velocity_update),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<VelocityUpdateCallback>(&mut self.velocity_update_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for braking strength change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_braking_strength_change(
+        chan: DcMotorHandle,
+        ctx: *mut c_void,
+        braking_strength: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<BrakingStrengthChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, braking_strength);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive braking strength change callbacks.
+    pub fn set_on_braking_strength_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&DcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<BrakingStrengthChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDCMotor_setOnBrakingStrengthChangeHandler(
+                self.chan,
+                Some(Self::on_braking_strength_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<BrakingStrengthChangeCallback>(
+            &mut self.braking_strength_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    // Low-level, unsafe, callback for back-EMF change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_back_emf_change(chan: DcMotorHandle, ctx: *mut c_void, back_emf: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<BackEmfChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, back_emf);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive back-EMF change callbacks.
+    pub fn set_on_back_emf_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&DcMotor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<BackEmfChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDCMotor_setOnBackEMFChangeHandler(
+                self.chan,
+                Some(Self::on_back_emf_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<BackEmfChangeCallback>(&mut self.back_emf_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of velocity update events.
+    ///
+    /// This replaces any velocity update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn velocity_updates(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_velocity_update_handler(move |_, velocity| tx.send(velocity))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDCMotor_setOnVelocityUpdateHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<VelocityUpdateCallback>(self.velocity_update_cb.take());
+        }))
+    }
+
+    /// Gets a stream of braking strength change events.
+    ///
+    /// This replaces any braking strength change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn braking_strength_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_braking_strength_change_handler(move |_, braking_strength| {
+            tx.send(braking_strength)
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDCMotor_setOnBrakingStrengthChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<BrakingStrengthChangeCallback>(self.braking_strength_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of back-EMF change events.
+    ///
+    /// This replaces any back-EMF change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn back_emf_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_back_emf_change_handler(move |_, back_emf| tx.send(back_emf))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDCMotor_setOnBackEMFChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<BackEmfChangeCallback>(self.back_emf_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any velocity update, braking strength change, back-EMF change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<DcMotorChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_velocity_update_handler({
+            let tx = tx.clone();
+            move |_, velocity| {
+                let _ = tx.send(Event::new(EventKind::Change(DcMotorChange::Velocity(
+                    velocity,
+                ))));
+            }
+        })?;
+        self.set_on_braking_strength_change_handler({
+            let tx = tx.clone();
+            move |_, braking_strength| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    DcMotorChange::BrakingStrength(braking_strength),
+                )));
+            }
+        })?;
+        self.set_on_back_emf_change_handler({
+            let tx = tx.clone();
+            move |_, back_emf| {
+                let _ = tx.send(Event::new(EventKind::Change(DcMotorChange::BackEmf(
+                    back_emf,
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for DcMotor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for DcMotor {}
+
+impl Default for DcMotor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<DcMotorHandle> for DcMotor {
+    fn from(chan: DcMotorHandle) -> Self {
+        Self {
+            chan,
+            velocity_update_cb: None,
+            braking_strength_change_cb: None,
+            back_emf_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for DcMotor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetDCMotor_delete(&mut self.chan);
+            crate::drop_cb::<VelocityUpdateCallback>(self.velocity_update_cb.take());
+            crate::drop_cb::<BrakingStrengthChangeCallback>(self.braking_strength_change_cb.take());
+            crate::drop_cb::<BackEmfChangeCallback>(self.back_emf_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,561 @@
+// phidget-rs/src/dictionary.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetDictionaryHandle as DictionaryHandle, PhidgetHandle};
+use std::{
+    ffi::{CStr, CString},
+    mem,
+    os::raw::{c_char, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust add callback.
+pub type AddCallback = dyn Fn(&Dictionary, &str, &str) + Send + 'static;
+/// The function type for the safe Rust update callback.
+pub type UpdateCallback = dyn Fn(&Dictionary, &str, &str) + Send + 'static;
+/// The function type for the safe Rust remove callback.
+pub type RemoveCallback = dyn Fn(&Dictionary, &str) + Send + 'static;
+
+/// A key/value pair in a dictionary
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryEntry {
+    /// The key
+    pub key: String,
+    /// The value
+    pub value: String,
+}
+
+/// The value changes reported by a dictionary through `Dictionary::subscribe()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DictionaryChange {
+    /// A key/value pair was added
+    Add(DictionaryEntry),
+    /// The value of a key was updated
+    Update(DictionaryEntry),
+    /// A key was removed
+    Remove(String),
+}
+
+/// The size of the buffer used to read a value from the dictionary.
+const VALUE_BUF_LEN: usize = 8192;
+
+/// The initial size of the buffer used to read a list of keys from the
+/// dictionary. It is doubled each time the list doesn't fit.
+const KEY_LIST_BUF_LEN: usize = 65536;
+
+/// The largest buffer used to read a list of keys from the dictionary.
+const KEY_LIST_BUF_MAX: usize = 16 * 1024 * 1024;
+
+// Determines if a key list read from the library may have been cut off,
+// since it filled the whole buffer, leaving only the terminating nul.
+fn key_list_truncated(buf: &[c_char]) -> bool {
+    buf.iter()
+        .position(|&c| c == 0)
+        .map_or(true, |n| n + 1 >= buf.len())
+}
+
+/// Determines if the string is a valid dictionary key.
+///
+/// Keys must be non-empty, printable ASCII strings that fit within the
+/// length limits of the phidget22 library.
+pub fn is_valid_key(key: &str) -> bool {
+    match CString::new(key) {
+        Ok(key) => unsafe { ffi::Phidget_validDictionaryKey(key.as_ptr()) != 0 },
+        Err(_) => false,
+    }
+}
+
+// Converts a key into a C string, checking that it is valid.
+fn to_key(key: &str) -> Result<CString> {
+    let key = CString::new(key).map_err(|_| ReturnCode::InvalidArg)?;
+    if unsafe { ffi::Phidget_validDictionaryKey(key.as_ptr()) } == 0 {
+        return Err(ReturnCode::InvalidArg);
+    }
+    Ok(key)
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget dictionary
+pub struct Dictionary {
+    // Handle to the channel in the phidget22 library
+    chan: DictionaryHandle,
+    // Double-boxed AddCallback, if registered
+    add_cb: Option<*mut c_void>,
+    // Double-boxed UpdateCallback, if registered
+    update_cb: Option<*mut c_void>,
+    // Double-boxed RemoveCallback, if registered
+    remove_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Dictionary {
+    /// Create a new dictionary.
+    pub fn new() -> Self {
+        let mut chan: DictionaryHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetDictionary_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &DictionaryHandle {
+        &self.chan
+    }
+
+    /// Adds a new key/value pair to the dictionary.
+    ///
+    /// This fails with `ReturnCode::Exist` if the key is already in the
+    /// dictionary.
+    pub fn add(&self, key: &str, value: &str) -> Result<()> {
+        let key = to_key(key)?;
+        let value = CString::new(value).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDictionary_add(self.chan, key.as_ptr(), value.as_ptr())
+        })
+    }
+
+    /// Sets the value of a key, adding it to the dictionary if it is not
+    /// already there.
+    pub fn set(&self, key: &str, value: &str) -> Result<()> {
+        let key = to_key(key)?;
+        let value = CString::new(value).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDictionary_set(self.chan, key.as_ptr(), value.as_ptr())
+        })
+    }
+
+    /// Updates the value of a key that is already in the dictionary.
+    ///
+    /// This fails with `ReturnCode::NoEnt` if the key is not in the
+    /// dictionary.
+    pub fn update(&self, key: &str, value: &str) -> Result<()> {
+        let key = to_key(key)?;
+        let value = CString::new(value).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDictionary_update(self.chan, key.as_ptr(), value.as_ptr())
+        })
+    }
+
+    /// Gets the value of a key, or `None` if the key is not in the
+    /// dictionary.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let key = to_key(key)?;
+        let mut buf = vec![0 as c_char; VALUE_BUF_LEN];
+        let rc = unsafe {
+            ffi::PhidgetDictionary_get(self.chan, key.as_ptr(), buf.as_mut_ptr(), buf.len())
+        };
+        match ReturnCode::result(rc) {
+            Ok(()) => {
+                let value = unsafe { CStr::from_ptr(buf.as_ptr()) };
+                Ok(Some(value.to_string_lossy().into()))
+            }
+            Err(ReturnCode::NoEnt) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Determines if the key is in the dictionary.
+    pub fn contains_key(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Removes a key, and its value, from the dictionary.
+    pub fn remove(&self, key: &str) -> Result<()> {
+        let key = to_key(key)?;
+        ReturnCode::result(unsafe { ffi::PhidgetDictionary_remove(self.chan, key.as_ptr()) })
+    }
+
+    /// Removes all the keys and values from the dictionary.
+    pub fn clear(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetDictionary_removeAll(self.chan) })
+    }
+
+    /// Gets an iterator over the keys in the dictionary that start with
+    /// the specified prefix, in alphabetical order.
+    ///
+    /// An empty prefix iterates over all the keys.
+    ///
+    /// The keys are read into a buffer that grows if the list doesn't fit,
+    /// up to 16 MiB. A longer list fails with `ReturnCode::NoSPC` rather
+    /// than being cut short.
+    pub fn scan(&self, prefix: &str) -> Result<impl Iterator<Item = String>> {
+        let start = CString::new(prefix).map_err(|_| ReturnCode::InvalidArg)?;
+        let mut len = KEY_LIST_BUF_LEN;
+        let buf = loop {
+            let mut buf = vec![0 as c_char; len];
+            let res = ReturnCode::result(unsafe {
+                ffi::PhidgetDictionary_scan(self.chan, start.as_ptr(), buf.as_mut_ptr(), buf.len())
+            });
+            match res {
+                Ok(()) if !key_list_truncated(&buf) => break buf,
+                Ok(()) | Err(ReturnCode::NoSPC) | Err(ReturnCode::TooBig) => {
+                    if len >= KEY_LIST_BUF_MAX {
+                        return Err(ReturnCode::NoSPC);
+                    }
+                    len *= 2;
+                }
+                Err(err) => return Err(err),
+            }
+        };
+        let list = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        let prefix = prefix.to_string();
+        let keys: Vec<String> = list
+            .to_string_lossy()
+            .lines()
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(keys
+            .into_iter()
+            .take_while(move |key| key.starts_with(&prefix)))
+    }
+
+    // Low-level, unsafe, callback for add events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_add(
+        chan: DictionaryHandle,
+        ctx: *mut c_void,
+        key: *const c_char,
+        value: *const c_char,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<AddCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(
+                &ch,
+                &CStr::from_ptr(key).to_string_lossy(),
+                &CStr::from_ptr(value).to_string_lossy(),
+            );
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive add callbacks.
+    pub fn set_on_add_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Dictionary, &str, &str) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<AddCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc =
+            unsafe { ffi::PhidgetDictionary_setOnAddHandler(self.chan, Some(Self::on_add), ctx) };
+        self.retired_cbs
+            .update::<AddCallback>(&mut self.add_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_update(
+        chan: DictionaryHandle,
+        ctx: *mut c_void,
+        key: *const c_char,
+        value: *const c_char,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<UpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(
+                &ch,
+                &CStr::from_ptr(key).to_string_lossy(),
+                &CStr::from_ptr(value).to_string_lossy(),
+            );
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive update callbacks.
+    pub fn set_on_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Dictionary, &str, &str) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<UpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDictionary_setOnUpdateHandler(self.chan, Some(Self::on_update), ctx)
+        };
+        self.retired_cbs
+            .update::<UpdateCallback>(&mut self.update_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for remove events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_remove(chan: DictionaryHandle, ctx: *mut c_void, key: *const c_char) {
+        if !ctx.is_null() {
+            let cb: &mut Box<RemoveCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, &CStr::from_ptr(key).to_string_lossy());
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive remove callbacks.
+    pub fn set_on_remove_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Dictionary, &str) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<RemoveCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDictionary_setOnRemoveHandler(self.chan, Some(Self::on_remove), ctx)
+        };
+        self.retired_cbs
+            .update::<RemoveCallback>(&mut self.remove_cb, ctx, rc)
+    }
+
+    /// Gets a stream of add events.
+    ///
+    /// This replaces any add handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn adds(&mut self) -> Result<crate::stream::EventStream<'_, DictionaryEntry>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_add_handler(move |_, key, value| {
+            tx.send(DictionaryEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDictionary_setOnAddHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs.retire::<AddCallback>(self.add_cb.take());
+        }))
+    }
+
+    /// Gets a stream of update events.
+    ///
+    /// This replaces any update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn updates(&mut self) -> Result<crate::stream::EventStream<'_, DictionaryEntry>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_update_handler(move |_, key, value| {
+            tx.send(DictionaryEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDictionary_setOnUpdateHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<UpdateCallback>(self.update_cb.take());
+        }))
+    }
+
+    /// Gets a stream of remove events.
+    ///
+    /// This replaces any remove handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn removes(&mut self) -> Result<crate::stream::EventStream<'_, String>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_remove_handler(move |_, key| tx.send(key.to_string()))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDictionary_setOnRemoveHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<RemoveCallback>(self.remove_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any add, update, remove, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<DictionaryChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_add_handler({
+            let tx = tx.clone();
+            move |_, key, value| {
+                let _ = tx.send(Event::new(EventKind::Change(DictionaryChange::Add(
+                    DictionaryEntry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_update_handler({
+            let tx = tx.clone();
+            move |_, key, value| {
+                let _ = tx.send(Event::new(EventKind::Change(DictionaryChange::Update(
+                    DictionaryEntry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_remove_handler({
+            let tx = tx.clone();
+            move |_, key| {
+                let _ = tx.send(Event::new(EventKind::Change(DictionaryChange::Remove(
+                    key.to_string(),
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Dictionary {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Dictionary {}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<DictionaryHandle> for Dictionary {
+    fn from(chan: DictionaryHandle) -> Self {
+        Self {
+            chan,
+            add_cb: None,
+            update_cb: None,
+            remove_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Dictionary {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetDictionary_delete(&mut self.chan);
+            crate::drop_cb::<AddCallback>(self.add_cb.take());
+            crate::drop_cb::<UpdateCallback>(self.update_cb.take());
+            crate::drop_cb::<RemoveCallback>(self.remove_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Makes a zero-filled key list buffer holding the string
+    fn key_buf(list: &str, len: usize) -> Vec<c_char> {
+        let mut buf = vec![0 as c_char; len];
+        for (dst, src) in buf.iter_mut().zip(list.bytes()) {
+            *dst = src as c_char;
+        }
+        buf
+    }
+
+    #[test]
+    fn key_list_truncation() {
+        assert!(!key_list_truncated(&key_buf("", 8)));
+        assert!(!key_list_truncated(&key_buf("a\nbc\n", 8)));
+        assert!(!key_list_truncated(&key_buf("a\nbc\nd", 8)));
+        assert!(key_list_truncated(&key_buf("a\nbc\nde", 8)));
+        assert!(key_list_truncated(&key_buf("a\nbc\ndef", 8)));
+    }
+}
@@ -10,7 +10,11 @@
 // to those terms.
 //
 
-use crate::{AttachCallback, DetachCallback, Error, GenericPhidget, Phidget, Result, ReturnCode};
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
 use phidget_sys::{self as ffi, PhidgetDigitalInputHandle, PhidgetHandle};
 use std::{
     mem,
@@ -33,6 +37,12 @@ pub struct DigitalInput {
     attach_cb: Option<*mut c_void>,
     // Double-boxed detach callback, if registered
     detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
 }
 
 /// InputMode for digital input
@@ -40,10 +50,10 @@ pub struct DigitalInput {
 #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(u32)]
 pub enum InputMode {
-    /// For using sensors with PNP transistor outputs.
-    PNP = 0,
     /// For using sensors with NPN transistor outputs.
-    NPN,
+    NPN = ffi::Phidget_InputMode_INPUT_MODE_NPN,
+    /// For using sensors with PNP transistor outputs.
+    PNP = ffi::Phidget_InputMode_INPUT_MODE_PNP,
 }
 
 impl TryFrom<u32> for InputMode {
@@ -52,8 +62,8 @@ impl TryFrom<u32> for InputMode {
     fn try_from(value: u32) -> Result<Self> {
         use InputMode::*;
         match value {
-            0 => Ok(PNP),
-            1 => Ok(NPN),
+            ffi::Phidget_InputMode_INPUT_MODE_NPN => Ok(NPN),
+            ffi::Phidget_InputMode_INPUT_MODE_PNP => Ok(PNP),
             _ => Err(ReturnCode::UnknownVal),
         }
     }
@@ -163,15 +173,52 @@ impl DigitalInput {
         // 1st box is fat ptr, 2nd is regular pointer.
         let cb: Box<Box<DigitalInputCallback>> = Box::new(Box::new(cb));
         let ctx = Box::into_raw(cb) as *mut c_void;
-        self.cb = Some(ctx);
 
-        ReturnCode::result(unsafe {
+        let rc = unsafe {
             ffi::PhidgetDigitalInput_setOnStateChangeHandler(
                 self.chan,
                 Some(Self::on_state_change),
                 ctx,
             )
-        })
+        };
+        self.retired_cbs
+            .update::<DigitalInputCallback>(&mut self.cb, ctx, rc)
+    }
+
+    /// Gets a stream of state change events.
+    ///
+    /// This replaces any state change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn state_changes(&mut self) -> Result<crate::stream::EventStream<'_, bool>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_state_change_handler(move |_, state| tx.send(state != 0))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDigitalInput_setOnStateChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<DigitalInputCallback>(self.cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any state change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<bool>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_state_change_handler({
+            let tx = tx.clone();
+            move |_, state| {
+                let _ = tx.send(Event::new(EventKind::Change(state != 0)));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
     }
 
     /// Sets a handler to receive attach callbacks
@@ -180,7 +227,8 @@ impl DigitalInput {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
-        self.attach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
         Ok(())
     }
 
@@ -190,7 +238,30 @@ impl DigitalInput {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
-        self.detach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
         Ok(())
     }
 }
@@ -199,6 +270,10 @@ impl Phidget for DigitalInput {
     fn as_handle(&mut self) -> PhidgetHandle {
         self.chan as PhidgetHandle
     }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
 }
 
 unsafe impl Send for DigitalInput {}
@@ -216,6 +291,9 @@ impl From<PhidgetDigitalInputHandle> for DigitalInput {
             cb: None,
             attach_cb: None,
             detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
         }
     }
 }
@@ -230,6 +308,8 @@ impl Drop for DigitalInput {
             crate::drop_cb::<DigitalInputCallback>(self.cb.take());
             crate::drop_cb::<AttachCallback>(self.attach_cb.take());
             crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
         }
     }
 }
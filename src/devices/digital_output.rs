@@ -10,9 +10,13 @@
 // to those terms.
 //
 
-use crate::{AttachCallback, DetachCallback, GenericPhidget, Phidget, Result, ReturnCode};
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
 use phidget_sys::{self as ffi, PhidgetDigitalOutputHandle, PhidgetHandle};
-use std::{os::raw::c_void, ptr};
+use std::{convert::Infallible, os::raw::c_void, ptr};
 
 /// Phidget digital output
 pub struct DigitalOutput {
@@ -22,6 +26,12 @@ pub struct DigitalOutput {
     attach_cb: Option<*mut c_void>,
     // Double-boxed detach callback, if registered
     detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
 }
 
 impl DigitalOutput {
@@ -56,7 +66,7 @@ impl DigitalOutput {
         })?;
         Ok(())
     }
-    /// Set  duty cycle async
+    // /// Set  duty cycle async
     // pub async fn set_duty_cycle_async(&self, duty_cycle: f64) -> Result<()> {
     //     _ = duty_cycle;
     //     unimplemented!();
@@ -150,7 +160,7 @@ impl DigitalOutput {
         Ok(())
     }
 
-    /// Set led current limit async
+    // /// Set led current limit async
     // pub async fn set_led_current_limit_async(&self, led_current_limit: f64) -> Result<()> {
     //     _ = led_current_limit;
     //     unimplemented!()
@@ -198,7 +208,7 @@ impl DigitalOutput {
         ReturnCode::result(unsafe { ffi::PhidgetDigitalOutput_setState(self.chan, state as i32) })
     }
 
-    /// Set state async
+    // /// Set state async
     // pub async fn set_state_async(&self, state: bool) -> Result<()> {
     //     _ = state;
     //     unimplemented!();
@@ -211,13 +221,27 @@ impl DigitalOutput {
         Ok(value != 0)
     }
 
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any attach, detach, and error handlers that were
+    /// previously set, and returns a receiver for the events. The channel
+    /// has no value change events of its own.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<Infallible>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
     /// Sets a handler to receive attach callbacks
     pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
     where
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
-        self.attach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
         Ok(())
     }
 
@@ -227,7 +251,30 @@ impl DigitalOutput {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
-        self.detach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
         Ok(())
     }
 }
@@ -236,6 +283,10 @@ impl Phidget for DigitalOutput {
     fn as_handle(&mut self) -> PhidgetHandle {
         self.chan as PhidgetHandle
     }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
 }
 
 unsafe impl Send for DigitalOutput {}
@@ -252,6 +303,9 @@ impl From<PhidgetDigitalOutputHandle> for DigitalOutput {
             chan,
             attach_cb: None,
             detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
         }
     }
 }
@@ -265,6 +319,8 @@ impl Drop for DigitalOutput {
             ffi::PhidgetDigitalOutput_delete(&mut self.chan);
             crate::drop_cb::<AttachCallback>(self.attach_cb.take());
             crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
         }
     }
 }
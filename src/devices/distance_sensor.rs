@@ -0,0 +1,455 @@
+// phidget-rs/src/distance_sensor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetDistanceSensorHandle as DistanceSensorHandle, PhidgetHandle,
+};
+use std::{
+    mem,
+    os::raw::{c_int, c_void},
+    ptr, slice,
+};
+
+/// The function type for the safe Rust distance change callback.
+pub type DistanceChangeCallback = dyn Fn(&DistanceSensor, u32) + Send + 'static;
+/// The function type for the safe Rust sonar reflections update callback.
+pub type SonarReflectionsUpdateCallback =
+    dyn Fn(&DistanceSensor, &[SonarReflection]) + Send + 'static;
+
+/// The value changes reported by the channel through `DistanceSensor::subscribe()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistanceSensorChange {
+    /// Distance change
+    DistanceChange(u32),
+    /// Sonar reflections update
+    SonarReflections(Vec<SonarReflection>),
+}
+
+/// The maximum number of sonar reflections reported by the sensor.
+pub const MAX_SONAR_REFLECTIONS: usize = 8;
+
+/// A sonar reflection, or echo, detected by a sonar distance sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SonarReflection {
+    /// The distance to the object, in mm
+    pub distance: u32,
+    /// The relative amplitude of the echo
+    pub amplitude: u32,
+}
+
+// Collects the reflections from the parallel distance and amplitude arrays
+// that are reported by the phidget22 library.
+unsafe fn sonar_reflections(
+    distances: *const u32,
+    amplitudes: *const u32,
+    count: u32,
+) -> Vec<SonarReflection> {
+    if distances.is_null() || amplitudes.is_null() {
+        return Vec::new();
+    }
+    let n = (count as usize).min(MAX_SONAR_REFLECTIONS);
+    let distances = slice::from_raw_parts(distances, n);
+    let amplitudes = slice::from_raw_parts(amplitudes, n);
+    distances
+        .iter()
+        .zip(amplitudes)
+        .map(|(&distance, &amplitude)| SonarReflection {
+            distance,
+            amplitude,
+        })
+        .collect()
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget distance sensor
+pub struct DistanceSensor {
+    // Handle to the channel in the phidget22 library
+    chan: DistanceSensorHandle,
+    // Double-boxed DistanceChangeCallback, if registered
+    distance_change_cb: Option<*mut c_void>,
+    // Double-boxed SonarReflectionsUpdateCallback, if registered
+    sonar_reflections_update_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl DistanceSensor {
+    /// Create a new distance sensor.
+    pub fn new() -> Self {
+        let mut chan: DistanceSensorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetDistanceSensor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &DistanceSensorHandle {
+        &self.chan
+    }
+
+    /// Gets the measured distance, in mm.
+    pub fn distance(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getDistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum distance that can be measured, in mm.
+    pub fn min_distance(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getMinDistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum distance that can be measured, in mm.
+    pub fn max_distance(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getMaxDistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the distance change trigger.
+    pub fn distance_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getDistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in distance, in mm, that will fire
+    /// a distance change event.
+    pub fn set_distance_change_trigger(&self, distance_change_trigger: u32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_setDistanceChangeTrigger(self.chan, distance_change_trigger)
+        })
+    }
+
+    /// Gets the minimum distance change trigger.
+    pub fn min_distance_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getMinDistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum distance change trigger.
+    pub fn max_distance_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getMaxDistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the most recent sonar reflections, ordered from the closest
+    /// to the farthest object.
+    pub fn sonar_reflections(&self) -> Result<Vec<SonarReflection>> {
+        let mut distances = [0; MAX_SONAR_REFLECTIONS];
+        let mut amplitudes = [0; MAX_SONAR_REFLECTIONS];
+        let mut count = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getSonarReflections(
+                self.chan,
+                &mut distances,
+                &mut amplitudes,
+                &mut count,
+            )
+        })?;
+        Ok(unsafe { sonar_reflections(distances.as_ptr(), amplitudes.as_ptr(), count) })
+    }
+
+    /// Determines if the sonar is in quiet mode.
+    pub fn sonar_quiet_mode(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_getSonarQuietMode(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Sets the sonar quiet mode.
+    ///
+    /// In quiet mode, the sensor stays silent between measurements, which
+    /// reduces the audible noise but also the maximum data rate.
+    pub fn set_sonar_quiet_mode(&self, quiet: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetDistanceSensor_setSonarQuietMode(self.chan, c_int::from(quiet))
+        })
+    }
+
+    // Low-level, unsafe, callback for distance change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_distance_change(
+        chan: DistanceSensorHandle,
+        ctx: *mut c_void,
+        distance: u32,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<DistanceChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, distance);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive distance change callbacks.
+    pub fn set_on_distance_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&DistanceSensor, u32) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<DistanceChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDistanceSensor_setOnDistanceChangeHandler(
+                self.chan,
+                Some(Self::on_distance_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<DistanceChangeCallback>(&mut self.distance_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for sonar reflections update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_sonar_reflections_update(
+        chan: DistanceSensorHandle,
+        ctx: *mut c_void,
+        distances: *const u32,
+        amplitudes: *const u32,
+        count: u32,
+    ) {
+        if !ctx.is_null() {
+            let reflections = sonar_reflections(distances, amplitudes, count);
+            let cb: &mut Box<SonarReflectionsUpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, &reflections);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive sonar reflections update callbacks.
+    pub fn set_on_sonar_reflections_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&DistanceSensor, &[SonarReflection]) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<SonarReflectionsUpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetDistanceSensor_setOnSonarReflectionsUpdateHandler(
+                self.chan,
+                Some(Self::on_sonar_reflections_update),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<SonarReflectionsUpdateCallback>(
+            &mut self.sonar_reflections_update_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of distance change events.
+    ///
+    /// This replaces any distance change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn distance_changes(&mut self) -> Result<crate::stream::EventStream<'_, u32>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_distance_change_handler(move |_, distance| tx.send(distance))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDistanceSensor_setOnDistanceChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<DistanceChangeCallback>(self.distance_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of sonar reflections update events.
+    ///
+    /// This replaces any sonar reflections update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn sonar_reflections_updates(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, Vec<SonarReflection>>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_sonar_reflections_update_handler(move |_, reflections| {
+            tx.send(reflections.to_vec())
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetDistanceSensor_setOnSonarReflectionsUpdateHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<SonarReflectionsUpdateCallback>(self.sonar_reflections_update_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any distance change, sonar reflections update, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<DistanceSensorChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_distance_change_handler({
+            let tx = tx.clone();
+            move |_, distance| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    DistanceSensorChange::DistanceChange(distance),
+                )));
+            }
+        })?;
+        self.set_on_sonar_reflections_update_handler({
+            let tx = tx.clone();
+            move |_, reflections| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    DistanceSensorChange::SonarReflections(reflections.to_vec()),
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for DistanceSensor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for DistanceSensor {}
+
+impl Default for DistanceSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<DistanceSensorHandle> for DistanceSensor {
+    fn from(chan: DistanceSensorHandle) -> Self {
+        Self {
+            chan,
+            distance_change_cb: None,
+            sonar_reflections_update_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for DistanceSensor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetDistanceSensor_delete(&mut self.chan);
+            crate::drop_cb::<DistanceChangeCallback>(self.distance_change_cb.take());
+            crate::drop_cb::<SonarReflectionsUpdateCallback>(
+                self.sonar_reflections_update_cb.take(),
+            );
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
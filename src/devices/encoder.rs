@@ -0,0 +1,409 @@
+// phidget-rs/src/encoder.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetEncoderHandle as EncoderHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust position change callback.
+pub type PositionChangeCallback = dyn Fn(&Encoder, i32, f64, bool) + Send + 'static;
+
+/// A position change reported by an encoder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncoderPositionChange {
+    /// The change in position since the last event, in quadrature counts
+    pub position_change: i32,
+    /// The time elapsed since the last event, in milliseconds
+    pub time_change: f64,
+    /// Whether the index channel was triggered during the interval
+    pub index_triggered: bool,
+}
+
+/// The electrical interface of the encoder inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum EncoderIoMode {
+    /// Push-pull outputs
+    PushPull = ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_PUSH_PULL,
+    /// Line driver outputs, with 2.2k pull-down resistors
+    LineDriver2K2 = ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_LINE_DRIVER_2K2,
+    /// Line driver outputs, with 10k pull-down resistors
+    LineDriver10K = ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_LINE_DRIVER_10K,
+    /// Open collector outputs, with 2.2k pull-up resistors
+    OpenCollector2K2 = ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_OPEN_COLLECTOR_2K2,
+    /// Open collector outputs, with 10k pull-up resistors
+    OpenCollector10K = ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_OPEN_COLLECTOR_10K,
+}
+
+impl TryFrom<u32> for EncoderIoMode {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use EncoderIoMode::*;
+        match val {
+            ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_PUSH_PULL => Ok(PushPull),
+            ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_LINE_DRIVER_2K2 => Ok(LineDriver2K2),
+            ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_LINE_DRIVER_10K => Ok(LineDriver10K),
+            ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_OPEN_COLLECTOR_2K2 => Ok(OpenCollector2K2),
+            ffi::Phidget_EncoderIOMode_ENCODER_IO_MODE_OPEN_COLLECTOR_10K => Ok(OpenCollector10K),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+impl EncoderPositionChange {
+    /// Gets the rate of change of the position, in counts per second.
+    ///
+    /// This is zero for the first event after the channel is opened,
+    /// which has no time reference.
+    pub fn counts_per_sec(&self) -> f64 {
+        if self.time_change > 0.0 {
+            1000.0 * f64::from(self.position_change) / self.time_change
+        }
+        else {
+            0.0
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget encoder input
+pub struct Encoder {
+    // Handle to the channel in the phidget22 library
+    chan: EncoderHandle,
+    // Double-boxed PositionChangeCallback, if registered
+    position_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Encoder {
+    /// Create a new encoder input.
+    pub fn new() -> Self {
+        let mut chan: EncoderHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetEncoder_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &EncoderHandle {
+        &self.chan
+    }
+
+    /// Determines whether the encoder input is enabled.
+    pub fn enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_getEnabled(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the encoder input.
+    pub fn set_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetEncoder_setEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Gets the position at which the index channel was last triggered.
+    pub fn index_position(&self) -> Result<i64> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_getIndexPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the IO mode of the encoder input.
+    pub fn io_mode(&self) -> Result<EncoderIoMode> {
+        let mut value: ffi::Phidget_EncoderIOMode = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_getIOMode(self.chan, &mut value) })?;
+        EncoderIoMode::try_from(value)
+    }
+
+    /// Sets the IO mode of the encoder input to match the encoder outputs.
+    pub fn set_io_mode(&self, io_mode: EncoderIoMode) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_setIOMode(self.chan, io_mode as c_uint) })
+    }
+
+    /// Gets the position, in quadrature counts.
+    pub fn position(&self) -> Result<i64> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_getPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the position, in quadrature counts. This also adjusts the
+    /// index position by the same offset.
+    pub fn set_position(&self, position: i64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetEncoder_setPosition(self.chan, position) })
+    }
+
+    /// Gets the position change trigger.
+    pub fn position_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetEncoder_getPositionChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum position change that will fire a position change event.
+    pub fn set_position_change_trigger(&self, position_change_trigger: u32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetEncoder_setPositionChangeTrigger(self.chan, position_change_trigger)
+        })
+    }
+
+    /// Gets the minimum position change trigger.
+    pub fn min_position_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetEncoder_getMinPositionChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum position change trigger.
+    pub fn max_position_change_trigger(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetEncoder_getMaxPositionChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for position change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_change(
+        chan: EncoderHandle,
+        ctx: *mut c_void,
+        position_change: c_int,
+        time_change: f64,
+        index_triggered: c_int,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position_change, time_change, index_triggered != 0);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position change callbacks.
+    pub fn set_on_position_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Encoder, i32, f64, bool) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetEncoder_setOnPositionChangeHandler(
+                self.chan,
+                Some(Self::on_position_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, EncoderPositionChange>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(
+            move |_, position_change, time_change, index_triggered| {
+                tx.send(EncoderPositionChange {
+                    position_change,
+                    time_change,
+                    index_triggered,
+                })
+            },
+        )?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetEncoder_setOnPositionChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any position change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<EncoderPositionChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, position_change, time_change, index_triggered| {
+                let _ = tx.send(Event::new(EventKind::Change(EncoderPositionChange {
+                    position_change,
+                    time_change,
+                    index_triggered,
+                })));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Encoder {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Encoder {}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<EncoderHandle> for Encoder {
+    fn from(chan: EncoderHandle) -> Self {
+        Self {
+            chan,
+            position_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetEncoder_delete(&mut self.chan);
+            crate::drop_cb::<PositionChangeCallback>(self.position_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Makes a position change event
+    fn change(position_change: i32, time_change: f64) -> EncoderPositionChange {
+        EncoderPositionChange {
+            position_change,
+            time_change,
+            index_triggered: false,
+        }
+    }
+
+    #[test]
+    fn counts_per_sec() {
+        assert_eq!(change(100, 250.0).counts_per_sec(), 400.0);
+        assert_eq!(change(-50, 100.0).counts_per_sec(), -500.0);
+        assert_eq!(change(0, 8.0).counts_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn counts_per_sec_zero_interval() {
+        // The first event after opening has no time reference
+        assert_eq!(change(100, 0.0).counts_per_sec(), 0.0);
+        assert_eq!(change(100, -1.0).counts_per_sec(), 0.0);
+    }
+}
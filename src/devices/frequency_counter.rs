@@ -0,0 +1,492 @@
+// phidget-rs/src/frequency_counter.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    devices::digital_input::{InputMode, PowerSupply},
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetFrequencyCounterHandle as FrequencyCounterHandle, PhidgetHandle,
+};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust count change callback.
+pub type CountChangeCallback = dyn Fn(&FrequencyCounter, u64, f64) + Send + 'static;
+/// The function type for the safe Rust frequency change callback.
+pub type FrequencyChangeCallback = dyn Fn(&FrequencyCounter, f64) + Send + 'static;
+
+/// A count change reported by a frequency counter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountChange {
+    /// The number of pulses counted since the last event
+    pub counts: u64,
+    /// The time elapsed since the last event, in milliseconds
+    pub time_change: f64,
+}
+
+/// The value changes reported by a frequency counter through `FrequencyCounter::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrequencyCounterChange {
+    /// Count change
+    Count(CountChange),
+    /// Frequency change
+    Frequency(f64),
+}
+
+/// The type of filter used on a frequency counter input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum FilterType {
+    /// For AC signals that cross zero
+    ZeroCrossing = ffi::PhidgetFrequencyCounter_FilterType_FILTER_TYPE_ZERO_CROSSING,
+    /// For signals that switch between logic levels
+    LogicLevel = ffi::PhidgetFrequencyCounter_FilterType_FILTER_TYPE_LOGIC_LEVEL,
+}
+
+impl TryFrom<u32> for FilterType {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use FilterType::*;
+        match val {
+            ffi::PhidgetFrequencyCounter_FilterType_FILTER_TYPE_ZERO_CROSSING => Ok(ZeroCrossing),
+            ffi::PhidgetFrequencyCounter_FilterType_FILTER_TYPE_LOGIC_LEVEL => Ok(LogicLevel),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget frequency counter
+pub struct FrequencyCounter {
+    // Handle to the channel in the phidget22 library
+    chan: FrequencyCounterHandle,
+    // Double-boxed CountChangeCallback, if registered
+    count_change_cb: Option<*mut c_void>,
+    // Double-boxed FrequencyChangeCallback, if registered
+    frequency_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl FrequencyCounter {
+    /// Create a new frequency counter.
+    pub fn new() -> Self {
+        let mut chan: FrequencyCounterHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetFrequencyCounter_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &FrequencyCounterHandle {
+        &self.chan
+    }
+
+    /// Gets the total number of pulses counted since the channel was opened,
+    /// or last reset.
+    pub fn count(&self) -> Result<u64> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getCount(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the time, in milliseconds, during which the pulses were counted.
+    pub fn time_elapsed(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getTimeElapsed(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Resets the count and elapsed time.
+    pub fn reset(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetFrequencyCounter_reset(self.chan) })
+    }
+
+    /// Determines whether the input is enabled.
+    pub fn enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the input.
+    pub fn set_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_setEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Gets the type of input filter.
+    pub fn filter_type(&self) -> Result<FilterType> {
+        let mut value: ffi::PhidgetFrequencyCounter_FilterType = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getFilterType(self.chan, &mut value)
+        })?;
+        FilterType::try_from(value)
+    }
+
+    /// Sets the type of input filter to match the signal.
+    pub fn set_filter_type(&self, filter_type: FilterType) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_setFilterType(self.chan, filter_type as c_uint)
+        })
+    }
+
+    /// Gets the most recent frequency, in Hz.
+    pub fn frequency(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getFrequency(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum frequency that can be measured, in Hz.
+    pub fn max_frequency(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getMaxFrequency(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the frequency cutoff, in Hz.
+    pub fn frequency_cutoff(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getFrequencyCutoff(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the frequency, in Hz, below which the frequency is
+    /// reported as zero.
+    pub fn set_frequency_cutoff(&self, frequency_cutoff: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_setFrequencyCutoff(self.chan, frequency_cutoff)
+        })
+    }
+
+    /// Gets the minimum frequency cutoff, in Hz.
+    pub fn min_frequency_cutoff(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getMinFrequencyCutoff(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum frequency cutoff, in Hz.
+    pub fn max_frequency_cutoff(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getMaxFrequencyCutoff(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the input mode.
+    pub fn input_mode(&self) -> Result<InputMode> {
+        let mut value: ffi::Phidget_InputMode = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getInputMode(self.chan, &mut value)
+        })?;
+        InputMode::try_from(value)
+    }
+
+    /// Sets the input mode to match the type of sensor output.
+    pub fn set_input_mode(&self, input_mode: InputMode) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_setInputMode(self.chan, input_mode as c_uint)
+        })
+    }
+
+    /// Gets the power supply voltage for the sensor.
+    pub fn power_supply(&self) -> Result<PowerSupply> {
+        let mut value: ffi::Phidget_PowerSupply = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_getPowerSupply(self.chan, &mut value)
+        })?;
+        PowerSupply::try_from(value)
+    }
+
+    /// Sets the power supply voltage for the sensor.
+    pub fn set_power_supply(&self, power_supply: PowerSupply) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetFrequencyCounter_setPowerSupply(self.chan, power_supply as c_uint)
+        })
+    }
+
+    // Low-level, unsafe, callback for count change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_count_change(
+        chan: FrequencyCounterHandle,
+        ctx: *mut c_void,
+        counts: u64,
+        time_change: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<CountChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, counts, time_change);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive count change callbacks.
+    pub fn set_on_count_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&FrequencyCounter, u64, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<CountChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetFrequencyCounter_setOnCountChangeHandler(
+                self.chan,
+                Some(Self::on_count_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<CountChangeCallback>(&mut self.count_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for frequency change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_frequency_change(
+        chan: FrequencyCounterHandle,
+        ctx: *mut c_void,
+        frequency: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<FrequencyChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, frequency);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive frequency change callbacks.
+    pub fn set_on_frequency_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&FrequencyCounter, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<FrequencyChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetFrequencyCounter_setOnFrequencyChangeHandler(
+                self.chan,
+                Some(Self::on_frequency_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<FrequencyChangeCallback>(&mut self.frequency_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of count change events.
+    ///
+    /// This replaces any count change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn count_changes(&mut self) -> Result<crate::stream::EventStream<'_, CountChange>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_count_change_handler(move |_, counts, time_change| {
+            tx.send(CountChange {
+                counts,
+                time_change,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetFrequencyCounter_setOnCountChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<CountChangeCallback>(self.count_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of frequency change events.
+    ///
+    /// This replaces any frequency change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn frequency_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_frequency_change_handler(move |_, frequency| tx.send(frequency))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetFrequencyCounter_setOnFrequencyChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<FrequencyChangeCallback>(self.frequency_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any count change, frequency change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<FrequencyCounterChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_count_change_handler({
+            let tx = tx.clone();
+            move |_, counts, time_change| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    FrequencyCounterChange::Count(CountChange {
+                        counts,
+                        time_change,
+                    }),
+                )));
+            }
+        })?;
+        self.set_on_frequency_change_handler({
+            let tx = tx.clone();
+            move |_, frequency| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    FrequencyCounterChange::Frequency(frequency),
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for FrequencyCounter {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for FrequencyCounter {}
+
+impl Default for FrequencyCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<FrequencyCounterHandle> for FrequencyCounter {
+    fn from(chan: FrequencyCounterHandle) -> Self {
+        Self {
+            chan,
+            count_change_cb: None,
+            frequency_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for FrequencyCounter {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetFrequencyCounter_delete(&mut self.chan);
+            crate::drop_cb::<CountChangeCallback>(self.count_change_cb.take());
+            crate::drop_cb::<FrequencyChangeCallback>(self.frequency_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,816 @@
+// phidget-rs/src/gps.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetGPSHandle as GpsHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust position change callback.
+pub type PositionChangeCallback = dyn Fn(&Gps, f64, f64, f64) + Send + 'static;
+/// The function type for the safe Rust heading change callback.
+pub type HeadingChangeCallback = dyn Fn(&Gps, f64, f64) + Send + 'static;
+/// The function type for the safe Rust position fix state change callback.
+pub type PositionFixStateChangeCallback = dyn Fn(&Gps, bool) + Send + 'static;
+
+/// A position reported by a GPS receiver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsPosition {
+    /// The latitude, in signed degrees
+    pub latitude: f64,
+    /// The longitude, in signed degrees
+    pub longitude: f64,
+    /// The altitude above mean sea level, in meters
+    pub altitude: f64,
+}
+
+/// A heading and speed reported by a GPS receiver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsHeading {
+    /// The true heading, in degrees
+    pub heading: f64,
+    /// The speed over the ground, in km/h
+    pub velocity: f64,
+}
+
+/// The value changes reported by a GPS receiver through `Gps::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpsChange {
+    /// Position change
+    Position(GpsPosition),
+    /// Heading change
+    Heading(GpsHeading),
+    /// Position fix state change
+    PositionFixState(bool),
+}
+
+/// A date reported by a GPS receiver, in UTC
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GpsDate {
+    /// The year
+    pub year: u16,
+    /// The month, 1-12
+    pub month: u8,
+    /// The day of the month, 1-31
+    pub day: u8,
+}
+
+impl From<ffi::PhidgetGPS_Date> for GpsDate {
+    fn from(date: ffi::PhidgetGPS_Date) -> Self {
+        Self {
+            year: date.tm_year as u16,
+            month: date.tm_mon as u8,
+            day: date.tm_mday as u8,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl GpsDate {
+    /// Converts the date to a `chrono` date.
+    /// This is `None` if the receiver has not yet reported a valid date.
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())
+    }
+}
+
+/// A time of day reported by a GPS receiver, in UTC
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GpsTime {
+    /// The hour, 0-23
+    pub hour: u8,
+    /// The minute, 0-59
+    pub minute: u8,
+    /// The second, 0-59
+    pub second: u8,
+    /// The milliseconds, 0-999
+    pub millisecond: u16,
+}
+
+impl From<ffi::PhidgetGPS_Time> for GpsTime {
+    fn from(time: ffi::PhidgetGPS_Time) -> Self {
+        Self {
+            hour: time.tm_hour as u8,
+            minute: time.tm_min as u8,
+            second: time.tm_sec as u8,
+            millisecond: time.tm_ms as u16,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl GpsTime {
+    /// Converts the time to a `chrono` time.
+    /// This is `None` if the receiver has not yet reported a valid time.
+    pub fn to_naive_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::from_hms_milli_opt(
+            self.hour.into(),
+            self.minute.into(),
+            self.second.into(),
+            self.millisecond.into(),
+        )
+    }
+}
+
+/// The NMEA GGA (fix information) sentence
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NmeaGga {
+    /// The latitude, in signed degrees
+    pub latitude: f64,
+    /// The longitude, in signed degrees
+    pub longitude: f64,
+    /// The fix quality
+    pub fix_quality: i16,
+    /// The number of satellites being tracked
+    pub num_satellites: i16,
+    /// The horizontal dilution of precision
+    pub horizontal_dilution: f64,
+    /// The altitude above mean sea level, in meters
+    pub altitude: f64,
+    /// The height of the geoid above the WGS84 ellipsoid, in meters
+    pub height_of_geoid: f64,
+}
+
+impl From<ffi::PhidgetGPS_GPGGA> for NmeaGga {
+    fn from(gga: ffi::PhidgetGPS_GPGGA) -> Self {
+        Self {
+            latitude: gga.latitude,
+            longitude: gga.longitude,
+            fix_quality: gga.fixQuality,
+            num_satellites: gga.numSatellites,
+            horizontal_dilution: gga.horizontalDilution,
+            altitude: gga.altitude,
+            height_of_geoid: gga.heightOfGeoid,
+        }
+    }
+}
+
+/// The NMEA GSA (DOP and active satellites) sentence
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NmeaGsa {
+    /// The selection mode, 'A' for automatic or 'M' for manual
+    pub mode: char,
+    /// The fix type: 1 for none, 2 for 2D, 3 for 3D
+    pub fix_type: i16,
+    /// The IDs of the satellites used for the fix
+    pub sat_used: [i16; 12],
+    /// The position dilution of precision
+    pub posn_dilution: f64,
+    /// The horizontal dilution of precision
+    pub horiz_dilution: f64,
+    /// The vertical dilution of precision
+    pub vert_dilution: f64,
+}
+
+impl From<ffi::PhidgetGPS_GPGSA> for NmeaGsa {
+    fn from(gsa: ffi::PhidgetGPS_GPGSA) -> Self {
+        Self {
+            mode: char::from(gsa.mode as u8),
+            fix_type: gsa.fixType,
+            sat_used: gsa.satUsed,
+            posn_dilution: gsa.posnDilution,
+            horiz_dilution: gsa.horizDilution,
+            vert_dilution: gsa.vertDilution,
+        }
+    }
+}
+
+/// The NMEA RMC (recommended minimum data) sentence
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NmeaRmc {
+    /// The status, 'A' for active or 'V' for void
+    pub status: char,
+    /// The latitude, in signed degrees
+    pub latitude: f64,
+    /// The longitude, in signed degrees
+    pub longitude: f64,
+    /// The speed over the ground, in knots
+    pub speed_knots: f64,
+    /// The track angle, in degrees
+    pub heading: f64,
+    /// The magnetic variation, in degrees
+    pub magnetic_variation: f64,
+    /// The mode indicator
+    pub mode: char,
+}
+
+impl From<ffi::PhidgetGPS_GPRMC> for NmeaRmc {
+    fn from(rmc: ffi::PhidgetGPS_GPRMC) -> Self {
+        Self {
+            status: char::from(rmc.status as u8),
+            latitude: rmc.latitude,
+            longitude: rmc.longitude,
+            speed_knots: rmc.speedKnots,
+            heading: rmc.heading,
+            magnetic_variation: rmc.magneticVariation,
+            mode: char::from(rmc.mode as u8),
+        }
+    }
+}
+
+/// The NMEA VTG (track made good and ground speed) sentence
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NmeaVtg {
+    /// The true track, in degrees
+    pub true_heading: f64,
+    /// The magnetic track, in degrees
+    pub magnetic_heading: f64,
+    /// The ground speed, in knots
+    pub speed_knots: f64,
+    /// The ground speed, in km/h
+    pub speed: f64,
+    /// The mode indicator
+    pub mode: char,
+}
+
+impl From<ffi::PhidgetGPS_GPVTG> for NmeaVtg {
+    fn from(vtg: ffi::PhidgetGPS_GPVTG) -> Self {
+        Self {
+            true_heading: vtg.trueHeading,
+            magnetic_heading: vtg.magneticHeading,
+            speed_knots: vtg.speedKnots,
+            speed: vtg.speed,
+            mode: char::from(vtg.mode as u8),
+        }
+    }
+}
+
+/// The most recent NMEA data received by a GPS receiver
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NmeaData {
+    /// The GGA (fix information) sentence
+    pub gga: NmeaGga,
+    /// The GSA (DOP and active satellites) sentence
+    pub gsa: NmeaGsa,
+    /// The RMC (recommended minimum data) sentence
+    pub rmc: NmeaRmc,
+    /// The VTG (track made good and ground speed) sentence
+    pub vtg: NmeaVtg,
+}
+
+impl From<ffi::PhidgetGPS_NMEAData> for NmeaData {
+    fn from(data: ffi::PhidgetGPS_NMEAData) -> Self {
+        Self {
+            gga: data.GGA.into(),
+            gsa: data.GSA.into(),
+            rmc: data.RMC.into(),
+            vtg: data.VTG.into(),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget GPS receiver
+pub struct Gps {
+    // Handle to the channel in the phidget22 library
+    chan: GpsHandle,
+    // Double-boxed PositionChangeCallback, if registered
+    position_change_cb: Option<*mut c_void>,
+    // Double-boxed HeadingChangeCallback, if registered
+    heading_change_cb: Option<*mut c_void>,
+    // Double-boxed PositionFixStateChangeCallback, if registered
+    position_fix_state_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Gps {
+    /// Create a new GPS receiver.
+    pub fn new() -> Self {
+        let mut chan: GpsHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetGPS_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &GpsHandle {
+        &self.chan
+    }
+
+    /// Gets the latitude, in signed degrees.
+    pub fn latitude(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getLatitude(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the longitude, in signed degrees.
+    pub fn longitude(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getLongitude(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the altitude above mean sea level, in meters.
+    pub fn altitude(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getAltitude(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the current true heading, in degrees.
+    pub fn heading(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getHeading(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the current speed over the ground, in km/h.
+    pub fn velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Determines whether the receiver has a position fix.
+    pub fn position_fix_state(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getPositionFixState(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Gets the current UTC date.
+    pub fn date(&self) -> Result<GpsDate> {
+        let mut value: ffi::PhidgetGPS_Date = unsafe { mem::zeroed() };
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getDate(self.chan, &mut value) })?;
+        Ok(value.into())
+    }
+
+    /// Gets the current UTC time.
+    pub fn time(&self) -> Result<GpsTime> {
+        let mut value: ffi::PhidgetGPS_Time = unsafe { mem::zeroed() };
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getTime(self.chan, &mut value) })?;
+        Ok(value.into())
+    }
+
+    /// Gets the current UTC date and time as a `chrono` timestamp.
+    #[cfg(feature = "chrono")]
+    pub fn date_time(&self) -> Result<chrono::NaiveDateTime> {
+        let date = self.date()?.to_naive_date();
+        let time = self.time()?.to_naive_time();
+        match (date, time) {
+            (Some(date), Some(time)) => Ok(date.and_time(time)),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+
+    /// Gets the most recent NMEA data received from the satellites.
+    pub fn nmea_data(&self) -> Result<NmeaData> {
+        let mut value: ffi::PhidgetGPS_NMEAData = unsafe { mem::zeroed() };
+        ReturnCode::result(unsafe { ffi::PhidgetGPS_getNMEAData(self.chan, &mut value) })?;
+        Ok(value.into())
+    }
+
+    // Low-level, unsafe, callback for position change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_change(
+        chan: GpsHandle,
+        ctx: *mut c_void,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, latitude, longitude, altitude);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position change callbacks.
+    pub fn set_on_position_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Gps, f64, f64, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetGPS_setOnPositionChangeHandler(
+                self.chan,
+                Some(Self::on_position_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for heading change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_heading_change(
+        chan: GpsHandle,
+        ctx: *mut c_void,
+        heading: f64,
+        velocity: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<HeadingChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, heading, velocity);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive heading change callbacks.
+    pub fn set_on_heading_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Gps, f64, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<HeadingChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetGPS_setOnHeadingChangeHandler(self.chan, Some(Self::on_heading_change), ctx)
+        };
+        self.retired_cbs
+            .update::<HeadingChangeCallback>(&mut self.heading_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for position fix state change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_fix_state_change(
+        chan: GpsHandle,
+        ctx: *mut c_void,
+        position_fix_state: c_int,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionFixStateChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position_fix_state != 0);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position fix state change callbacks.
+    pub fn set_on_position_fix_state_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Gps, bool) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionFixStateChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetGPS_setOnPositionFixStateChangeHandler(
+                self.chan,
+                Some(Self::on_position_fix_state_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<PositionFixStateChangeCallback>(
+            &mut self.position_fix_state_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(&mut self) -> Result<crate::stream::EventStream<'_, GpsPosition>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(move |_, latitude, longitude, altitude| {
+            tx.send(GpsPosition {
+                latitude,
+                longitude,
+                altitude,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetGPS_setOnPositionChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of heading change events.
+    ///
+    /// This replaces any heading change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn heading_changes(&mut self) -> Result<crate::stream::EventStream<'_, GpsHeading>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_heading_change_handler(move |_, heading, velocity| {
+            tx.send(GpsHeading { heading, velocity })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetGPS_setOnHeadingChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<HeadingChangeCallback>(self.heading_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of position fix state change events.
+    ///
+    /// This replaces any position fix state change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_fix_state_changes(&mut self) -> Result<crate::stream::EventStream<'_, bool>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_fix_state_change_handler(move |_, position_fix_state| {
+            tx.send(position_fix_state)
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetGPS_setOnPositionFixStateChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionFixStateChangeCallback>(self.position_fix_state_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any position change, heading change, position fix state change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<GpsChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, latitude, longitude, altitude| {
+                let _ = tx.send(Event::new(EventKind::Change(GpsChange::Position(
+                    GpsPosition {
+                        latitude,
+                        longitude,
+                        altitude,
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_heading_change_handler({
+            let tx = tx.clone();
+            move |_, heading, velocity| {
+                let _ = tx.send(Event::new(EventKind::Change(GpsChange::Heading(
+                    GpsHeading { heading, velocity },
+                ))));
+            }
+        })?;
+        self.set_on_position_fix_state_change_handler({
+            let tx = tx.clone();
+            move |_, position_fix_state| {
+                let _ = tx.send(Event::new(EventKind::Change(GpsChange::PositionFixState(
+                    position_fix_state,
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Gps {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Gps {}
+
+impl Default for Gps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<GpsHandle> for Gps {
+    fn from(chan: GpsHandle) -> Self {
+        Self {
+            chan,
+            position_change_cb: None,
+            heading_change_cb: None,
+            position_fix_state_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Gps {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetGPS_delete(&mut self.chan);
+            crate::drop_cb::<PositionChangeCallback>(self.position_change_cb.take());
+            crate::drop_cb::<HeadingChangeCallback>(self.heading_change_cb.take());
+            crate::drop_cb::<PositionFixStateChangeCallback>(
+                self.position_fix_state_change_cb.take(),
+            );
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::raw::c_char;
+
+    #[test]
+    fn date_time_from_ffi() {
+        let date = GpsDate::from(ffi::PhidgetGPS_Date {
+            tm_mday: 17,
+            tm_mon: 6,
+            tm_year: 2024,
+        });
+        assert_eq!(
+            date,
+            GpsDate {
+                year: 2024,
+                month: 6,
+                day: 17
+            }
+        );
+
+        let time = GpsTime::from(ffi::PhidgetGPS_Time {
+            tm_ms: 250,
+            tm_sec: 30,
+            tm_min: 45,
+            tm_hour: 13,
+        });
+        assert_eq!(
+            time,
+            GpsTime {
+                hour: 13,
+                minute: 45,
+                second: 30,
+                millisecond: 250
+            }
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_time_to_chrono() {
+        let date = GpsDate {
+            year: 2024,
+            month: 2,
+            day: 29,
+        };
+        assert_eq!(
+            date.to_naive_date(),
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+
+        let time = GpsTime {
+            hour: 23,
+            minute: 59,
+            second: 59,
+            millisecond: 999,
+        };
+        assert_eq!(
+            time.to_naive_time(),
+            chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 999)
+        );
+
+        // Before the receiver has a fix, the values are zero or invalid
+        assert_eq!(GpsDate::default().to_naive_date(), None);
+        let date = GpsDate {
+            year: 2023,
+            month: 2,
+            day: 29,
+        };
+        assert_eq!(date.to_naive_date(), None);
+        let time = GpsTime {
+            hour: 24,
+            ..GpsTime::default()
+        };
+        assert_eq!(time.to_naive_time(), None);
+    }
+
+    #[test]
+    fn nmea_data_from_ffi() {
+        let mut data: ffi::PhidgetGPS_NMEAData = unsafe { mem::zeroed() };
+        data.GGA.latitude = 45.5;
+        data.GGA.longitude = -73.5;
+        data.GGA.fixQuality = 1;
+        data.GGA.numSatellites = 8;
+        data.GGA.altitude = 30.0;
+        data.GSA.mode = b'A' as c_char;
+        data.GSA.fixType = 3;
+        data.GSA.satUsed[0] = 12;
+        data.GSA.posnDilution = 1.5;
+        data.RMC.status = b'A' as c_char;
+        data.RMC.speedKnots = 10.0;
+        data.RMC.mode = b'D' as c_char;
+        data.VTG.trueHeading = 90.0;
+        data.VTG.speed = 18.52;
+        data.VTG.mode = b'A' as c_char;
+
+        let nmea = NmeaData::from(data);
+        assert_eq!(nmea.gga.latitude, 45.5);
+        assert_eq!(nmea.gga.longitude, -73.5);
+        assert_eq!(nmea.gga.fix_quality, 1);
+        assert_eq!(nmea.gga.num_satellites, 8);
+        assert_eq!(nmea.gga.altitude, 30.0);
+        assert_eq!(nmea.gsa.mode, 'A');
+        assert_eq!(nmea.gsa.fix_type, 3);
+        assert_eq!(nmea.gsa.sat_used[0], 12);
+        assert_eq!(nmea.gsa.posn_dilution, 1.5);
+        assert_eq!(nmea.rmc.status, 'A');
+        assert_eq!(nmea.rmc.speed_knots, 10.0);
+        assert_eq!(nmea.rmc.mode, 'D');
+        assert_eq!(nmea.vtg.true_heading, 90.0);
+        assert_eq!(nmea.vtg.speed, 18.52);
+        assert_eq!(nmea.vtg.mode, 'A');
+    }
+}
@@ -0,0 +1,310 @@
+// phidget-rs/src/gyroscope.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetGyroscopeHandle as GyroscopeHandle, PhidgetHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust angular rate update callback.
+pub type AngularRateUpdateCallback = dyn Fn(&Gyroscope, [f64; 3], f64) + Send + 'static;
+
+/// An angular rate reading from a gyroscope
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AngularRateUpdate {
+    /// The angular rate for each axis, in degrees/sec
+    pub angular_rate: [f64; 3],
+    /// The timestamp of the reading, in milliseconds
+    pub timestamp: f64,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget gyroscope
+pub struct Gyroscope {
+    // Handle to the channel in the phidget22 library
+    chan: GyroscopeHandle,
+    // Double-boxed AngularRateUpdateCallback, if registered
+    angular_rate_update_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Gyroscope {
+    /// Create a new gyroscope.
+    pub fn new() -> Self {
+        let mut chan: GyroscopeHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetGyroscope_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &GyroscopeHandle {
+        &self.chan
+    }
+
+    /// Gets the angular rate for each axis, in degrees/sec.
+    pub fn angular_rate(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe { ffi::PhidgetGyroscope_getAngularRate(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum angular rate for each axis, in degrees/sec.
+    pub fn min_angular_rate(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetGyroscope_getMinAngularRate(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum angular rate for each axis, in degrees/sec.
+    pub fn max_angular_rate(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetGyroscope_getMaxAngularRate(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Re-zeroes the gyroscope. The device must be held still while
+    /// this is in progress.
+    pub fn zero(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetGyroscope_zero(self.chan) })
+    }
+
+    /// Gets the number of axes the gyroscope can measure.
+    pub fn axis_count(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetGyroscope_getAxisCount(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Determines whether the internal heater is enabled.
+    pub fn heating_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetGyroscope_getHeatingEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the internal heater, which keeps the sensor
+    /// at a stable temperature.
+    pub fn set_heating_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetGyroscope_setHeatingEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Gets the timestamp of the most recent reading, in milliseconds.
+    pub fn timestamp(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetGyroscope_getTimestamp(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for angular rate update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_angular_rate_update(
+        chan: GyroscopeHandle,
+        ctx: *mut c_void,
+        angular_rate: *const f64,
+        timestamp: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<AngularRateUpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, *(angular_rate as *const [f64; 3]), timestamp);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive angular rate update callbacks.
+    pub fn set_on_angular_rate_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Gyroscope, [f64; 3], f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<AngularRateUpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetGyroscope_setOnAngularRateUpdateHandler(
+                self.chan,
+                Some(Self::on_angular_rate_update),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<AngularRateUpdateCallback>(
+            &mut self.angular_rate_update_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of angular rate update events.
+    ///
+    /// This replaces any angular rate update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn angular_rate_updates(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, AngularRateUpdate>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_angular_rate_update_handler(move |_, angular_rate, timestamp| {
+            tx.send(AngularRateUpdate {
+                angular_rate,
+                timestamp,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetGyroscope_setOnAngularRateUpdateHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<AngularRateUpdateCallback>(self.angular_rate_update_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any angular rate update, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<AngularRateUpdate>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_angular_rate_update_handler({
+            let tx = tx.clone();
+            move |_, angular_rate, timestamp| {
+                let _ = tx.send(Event::new(EventKind::Change(AngularRateUpdate {
+                    angular_rate,
+                    timestamp,
+                })));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Gyroscope {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Gyroscope {}
+
+impl Default for Gyroscope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<GyroscopeHandle> for Gyroscope {
+    fn from(chan: GyroscopeHandle) -> Self {
+        Self {
+            chan,
+            angular_rate_update_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Gyroscope {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetGyroscope_delete(&mut self.chan);
+            crate::drop_cb::<AngularRateUpdateCallback>(self.angular_rate_update_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -10,9 +10,14 @@
 // to those terms.
 //
 
-use crate::{AttachCallback, DetachCallback, Error, GenericPhidget, Phidget, Result, ReturnCode};
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
 use phidget_sys::{self as ffi, PhidgetHandle, PhidgetHubHandle as HubHandle};
 use std::{
+    convert::Infallible,
     os::raw::{c_int, c_uint, c_void},
     ptr,
 };
@@ -59,6 +64,12 @@ pub struct Hub {
     attach_cb: Option<*mut c_void>,
     // Double-boxed detach callback, if registered
     detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
 }
 
 impl Hub {
@@ -85,13 +96,27 @@ impl Hub {
         ReturnCode::result(unsafe { ffi::PhidgetHub_setPortMode(self.chan, port, mode as c_uint) })
     }
 
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any attach, detach, and error handlers that were
+    /// previously set, and returns a receiver for the events. The channel
+    /// has no value change events of its own.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<Infallible>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
     /// Sets a handler to receive attach callbacks
     pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
     where
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
-        self.attach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
         Ok(())
     }
 
@@ -101,7 +126,30 @@ impl Hub {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
-        self.detach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
         Ok(())
     }
 }
@@ -110,6 +158,10 @@ impl Phidget for Hub {
     fn as_handle(&mut self) -> PhidgetHandle {
         self.chan as PhidgetHandle
     }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
 }
 
 unsafe impl Send for Hub {}
@@ -126,6 +178,9 @@ impl From<HubHandle> for Hub {
             chan,
             attach_cb: None,
             detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
         }
     }
 }
@@ -139,6 +194,8 @@ impl Drop for Hub {
             ffi::PhidgetHub_delete(&mut self.chan);
             crate::drop_cb::<AttachCallback>(self.attach_cb.take());
             crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
         }
     }
 }
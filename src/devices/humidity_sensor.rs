@@ -12,7 +12,11 @@
 //! Phidget Humidity sensor
 //!
 
-use crate::{AttachCallback, DetachCallback, GenericPhidget, Phidget, Result, ReturnCode};
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
 use phidget_sys::{
     self as ffi, PhidgetHandle, PhidgetHumiditySensorHandle as HumiditySensorHandle,
 };
@@ -31,6 +35,12 @@ pub struct HumiditySensor {
     attach_cb: Option<*mut c_void>,
     // Double-boxed detach callback, if registered
     detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
 }
 
 impl HumiditySensor {
@@ -80,15 +90,46 @@ impl HumiditySensor {
         // 1st box is fat ptr, 2nd is regular pointer.
         let cb: Box<Box<HumidityCallback>> = Box::new(Box::new(cb));
         let ctx = Box::into_raw(cb) as *mut c_void;
-        self.cb = Some(ctx);
 
-        ReturnCode::result(unsafe {
+        let rc = unsafe {
             ffi::PhidgetHumiditySensor_setOnHumidityChangeHandler(
                 self.chan,
                 Some(Self::on_humidity_change),
                 ctx,
             )
-        })
+        };
+        self.retired_cbs
+            .update::<HumidityCallback>(&mut self.cb, ctx, rc)
+    }
+
+    /// Gets a stream of humidity change events.
+    ///
+    /// This replaces any humidity change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn humidity_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_humidity_change_handler(move |_, h| tx.send(h))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetHumiditySensor_setOnHumidityChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs.retire::<HumidityCallback>(self.cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any humidity change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_humidity_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
     }
 
     /// Sets a handler to receive attach callbacks
@@ -97,7 +138,8 @@ impl HumiditySensor {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
-        self.attach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
         Ok(())
     }
 
@@ -107,7 +149,30 @@ impl HumiditySensor {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
-        self.detach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
         Ok(())
     }
 }
@@ -116,6 +181,10 @@ impl Phidget for HumiditySensor {
     fn as_handle(&mut self) -> PhidgetHandle {
         self.chan as PhidgetHandle
     }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
 }
 
 unsafe impl Send for HumiditySensor {}
@@ -133,6 +202,9 @@ impl From<HumiditySensorHandle> for HumiditySensor {
             cb: None,
             attach_cb: None,
             detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
         }
     }
 }
@@ -147,6 +219,8 @@ impl Drop for HumiditySensor {
             crate::drop_cb::<HumidityCallback>(self.cb.take());
             crate::drop_cb::<AttachCallback>(self.attach_cb.take());
             crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
         }
     }
 }
@@ -0,0 +1,747 @@
+// phidget-rs/src/ir.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetIRHandle as IrHandle};
+use std::{
+    ffi::{CStr, CString},
+    mem,
+    os::raw::{c_char, c_int, c_void},
+    ptr, slice,
+};
+
+/// The function type for the safe Rust code callback.
+pub type CodeCallback = dyn Fn(&Ir, &str, u32, bool) + Send + 'static;
+/// The function type for the safe Rust learn callback.
+pub type LearnCallback = dyn Fn(&Ir, &str, &IrCodeInfo) + Send + 'static;
+/// The function type for the safe Rust raw data callback.
+pub type RawDataCallback = dyn Fn(&Ir, &[u32]) + Send + 'static;
+
+/// A code received by an IR receiver
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrCode {
+    /// The code, as a hex string
+    pub code: String,
+    /// The number of bits in the code
+    pub bit_count: u32,
+    /// Whether the code is a repeat of the previous one
+    pub is_repeat: bool,
+}
+
+/// A code learned by an IR receiver.
+///
+/// This has all the information required to transmit the code again.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IrLearnedCode {
+    /// The code, as a hex string
+    pub code: String,
+    /// The format of the code, used to transmit it
+    pub code_info: IrCodeInfo,
+}
+
+/// The value changes reported by an IR receiver through `Ir::subscribe()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum IrChange {
+    /// Code
+    Code(IrCode),
+    /// Learn
+    Learn(IrLearnedCode),
+    /// Raw data
+    RawData(Vec<u32>),
+}
+
+/// The encoding of the data bits in an IR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum IrEncoding {
+    /// Unknown, let the library determine the encoding
+    Unknown = ffi::PhidgetIR_Encoding_IR_ENCODING_UNKNOWN,
+    /// Space encoding, where the bits are in the length of the spaces
+    Space = ffi::PhidgetIR_Encoding_IR_ENCODING_SPACE,
+    /// Pulse encoding, where the bits are in the length of the pulses
+    Pulse = ffi::PhidgetIR_Encoding_IR_ENCODING_PULSE,
+    /// Bi-phase (Manchester) encoding
+    BiPhase = ffi::PhidgetIR_Encoding_IR_ENCODING_BIPHASE,
+    /// RC5, a type of bi-phase encoding
+    Rc5 = ffi::PhidgetIR_Encoding_IR_ENCODING_RC5,
+    /// RC6, a type of bi-phase encoding
+    Rc6 = ffi::PhidgetIR_Encoding_IR_ENCODING_RC6,
+}
+
+impl TryFrom<u32> for IrEncoding {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use IrEncoding::*;
+        match val {
+            ffi::PhidgetIR_Encoding_IR_ENCODING_UNKNOWN => Ok(Unknown),
+            ffi::PhidgetIR_Encoding_IR_ENCODING_SPACE => Ok(Space),
+            ffi::PhidgetIR_Encoding_IR_ENCODING_PULSE => Ok(Pulse),
+            ffi::PhidgetIR_Encoding_IR_ENCODING_BIPHASE => Ok(BiPhase),
+            ffi::PhidgetIR_Encoding_IR_ENCODING_RC5 => Ok(Rc5),
+            ffi::PhidgetIR_Encoding_IR_ENCODING_RC6 => Ok(Rc6),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// How the length of an IR code is determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum IrLength {
+    /// Unknown, let the library determine the length
+    Unknown = ffi::PhidgetIR_Length_IR_LENGTH_UNKNOWN,
+    /// The code has a constant overall time, so the gap varies
+    Constant = ffi::PhidgetIR_Length_IR_LENGTH_CONSTANT,
+    /// The code has a constant gap, so the overall time varies
+    Variable = ffi::PhidgetIR_Length_IR_LENGTH_VARIABLE,
+}
+
+impl TryFrom<u32> for IrLength {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use IrLength::*;
+        match val {
+            ffi::PhidgetIR_Length_IR_LENGTH_UNKNOWN => Ok(Unknown),
+            ffi::PhidgetIR_Length_IR_LENGTH_CONSTANT => Ok(Constant),
+            ffi::PhidgetIR_Length_IR_LENGTH_VARIABLE => Ok(Variable),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// The description of how to transmit an IR code.
+///
+/// The times are in microseconds. The ones that are left at zero are
+/// filled in by the library with default values for the encoding.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IrCodeInfo {
+    /// The number of data bits in the code
+    pub bit_count: u32,
+    /// The encoding of the data bits
+    pub encoding: IrEncoding,
+    /// How the length of the code is determined
+    pub length: IrLength,
+    /// The gap time
+    pub gap: u32,
+    /// The trailing pulse time, or zero for none
+    pub trail: u32,
+    /// The header pulse and space times, or zero for none
+    pub header: [u32; 2],
+    /// The pulse and space times for a one bit
+    pub one: [u32; 2],
+    /// The pulse and space times for a zero bit
+    pub zero: [u32; 2],
+    /// The pulse and space times of the repeat code, if any.
+    /// This can contain up to 25 values.
+    pub repeat: Vec<u32>,
+    /// The minimum number of times to repeat the code when transmitting
+    pub min_repeat: u32,
+    /// The duty cycle of the carrier, from 0.1 to 0.5
+    pub duty_cycle: f64,
+    /// The carrier frequency, in Hz
+    pub carrier_frequency: u32,
+    /// The bits that are toggled on each repeat, as a hex string
+    pub toggle_mask: String,
+}
+
+impl Default for IrCodeInfo {
+    fn default() -> Self {
+        Self {
+            bit_count: 0,
+            encoding: IrEncoding::Unknown,
+            length: IrLength::Unknown,
+            gap: 0,
+            trail: 0,
+            header: [0; 2],
+            one: [0; 2],
+            zero: [0; 2],
+            repeat: Vec::new(),
+            min_repeat: 0,
+            duty_cycle: 0.0,
+            carrier_frequency: 0,
+            toggle_mask: String::new(),
+        }
+    }
+}
+
+impl IrCodeInfo {
+    // Converts the info into the struct used by the phidget22 library.
+    fn to_ffi(&self) -> Result<ffi::PhidgetIR_CodeInfo> {
+        let mut info: ffi::PhidgetIR_CodeInfo = unsafe { mem::zeroed() };
+
+        // The repeat and toggle mask arrays must be zero-terminated
+        if self.repeat.len() >= info.repeat.len() || self.toggle_mask.len() >= info.toggleMask.len()
+        {
+            return Err(ReturnCode::InvalidArg);
+        }
+
+        info.bitCount = self.bit_count;
+        info.encoding = self.encoding as ffi::PhidgetIR_Encoding;
+        info.length = self.length as ffi::PhidgetIR_Length;
+        info.gap = self.gap;
+        info.trail = self.trail;
+        info.header = self.header;
+        info.one = self.one;
+        info.zero = self.zero;
+        info.repeat[..self.repeat.len()].copy_from_slice(&self.repeat);
+        info.minRepeat = self.min_repeat;
+        info.dutyCycle = self.duty_cycle;
+        info.carrierFrequency = self.carrier_frequency;
+        for (dst, src) in info.toggleMask.iter_mut().zip(self.toggle_mask.bytes()) {
+            *dst = src as c_char;
+        }
+        Ok(info)
+    }
+
+    // Converts the info from the struct used by the phidget22 library,
+    // taking an encoding or length that isn't known to this crate as
+    // `Unknown`, so the library determines it again when transmitting.
+    fn from_ffi_lossy(info: &ffi::PhidgetIR_CodeInfo) -> Self {
+        let n = info
+            .repeat
+            .iter()
+            .position(|&t| t == 0)
+            .unwrap_or(info.repeat.len());
+        let toggle_mask = info
+            .toggleMask
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| char::from(c as u8))
+            .collect();
+
+        Self {
+            bit_count: info.bitCount,
+            encoding: IrEncoding::try_from(info.encoding).unwrap_or(IrEncoding::Unknown),
+            length: IrLength::try_from(info.length).unwrap_or(IrLength::Unknown),
+            gap: info.gap,
+            trail: info.trail,
+            header: info.header,
+            one: info.one,
+            zero: info.zero,
+            repeat: info.repeat[..n].to_vec(),
+            min_repeat: info.minRepeat,
+            duty_cycle: info.dutyCycle,
+            carrier_frequency: info.carrierFrequency,
+            toggle_mask,
+        }
+    }
+}
+
+impl TryFrom<&ffi::PhidgetIR_CodeInfo> for IrCodeInfo {
+    type Error = Error;
+
+    fn try_from(info: &ffi::PhidgetIR_CodeInfo) -> Result<Self> {
+        IrEncoding::try_from(info.encoding)?;
+        IrLength::try_from(info.length)?;
+        Ok(Self::from_ffi_lossy(info))
+    }
+}
+
+// The size of the buffer used to read a code string
+const CODE_BUF_LEN: usize = ffi::IR_MAX_CODE_STR_LENGTH as usize;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget IR transmitter/receiver
+pub struct Ir {
+    // Handle to the channel in the phidget22 library
+    chan: IrHandle,
+    // Double-boxed CodeCallback, if registered
+    code_cb: Option<*mut c_void>,
+    // Double-boxed LearnCallback, if registered
+    learn_cb: Option<*mut c_void>,
+    // Double-boxed RawDataCallback, if registered
+    raw_data_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Ir {
+    /// Create a new IR transmitter/receiver.
+    pub fn new() -> Self {
+        let mut chan: IrHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetIR_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &IrHandle {
+        &self.chan
+    }
+
+    /// Gets the last code that was received, and its bit count.
+    pub fn last_code(&self) -> Result<(String, u32)> {
+        let mut buf = [0 as c_char; CODE_BUF_LEN];
+        let mut bit_count = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetIR_getLastCode(self.chan, buf.as_mut_ptr(), buf.len(), &mut bit_count)
+        })?;
+        let code = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok((code.to_string_lossy().into(), bit_count))
+    }
+
+    /// Gets the last code that was learned.
+    pub fn last_learned_code(&self) -> Result<IrLearnedCode> {
+        let mut buf = [0 as c_char; CODE_BUF_LEN];
+        let mut info: ffi::PhidgetIR_CodeInfo = unsafe { mem::zeroed() };
+        ReturnCode::result(unsafe {
+            ffi::PhidgetIR_getLastLearnedCode(self.chan, buf.as_mut_ptr(), buf.len(), &mut info)
+        })?;
+        let code = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok(IrLearnedCode {
+            code: code.to_string_lossy().into(),
+            code_info: IrCodeInfo::try_from(&info)?,
+        })
+    }
+
+    /// Transmits a code, as a hex string, with the specified format.
+    pub fn transmit(&self, code: &str, code_info: &IrCodeInfo) -> Result<()> {
+        let code = CString::new(code).map_err(|_| ReturnCode::InvalidArg)?;
+        let mut info = code_info.to_ffi()?;
+        ReturnCode::result(unsafe { ffi::PhidgetIR_transmit(self.chan, code.as_ptr(), &mut info) })
+    }
+
+    /// Transmits a code that was previously learned.
+    pub fn transmit_learned(&self, learned: &IrLearnedCode) -> Result<()> {
+        self.transmit(&learned.code, &learned.code_info)
+    }
+
+    /// Transmits raw data, as a series of pulse and space times, in
+    /// microseconds. This must start and end with a pulse.
+    ///
+    /// A zero carrier frequency, duty cycle, or gap selects the default.
+    pub fn transmit_raw(
+        &self,
+        data: &[u32],
+        carrier_frequency: u32,
+        duty_cycle: f64,
+        gap: u32,
+    ) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetIR_transmitRaw(
+                self.chan,
+                data.as_ptr(),
+                data.len(),
+                carrier_frequency,
+                duty_cycle,
+                gap,
+            )
+        })
+    }
+
+    /// Transmits a repeat of the last code that was sent.
+    /// This must be called within the gap time of the previous transmission.
+    pub fn transmit_repeat(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetIR_transmitRepeat(self.chan) })
+    }
+
+    // Low-level, unsafe, callback for code events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_code(
+        chan: IrHandle,
+        ctx: *mut c_void,
+        code: *const c_char,
+        bit_count: u32,
+        is_repeat: c_int,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<CodeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(
+                &ch,
+                &CStr::from_ptr(code).to_string_lossy(),
+                bit_count,
+                is_repeat != 0,
+            );
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive code callbacks.
+    pub fn set_on_code_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Ir, &str, u32, bool) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<CodeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe { ffi::PhidgetIR_setOnCodeHandler(self.chan, Some(Self::on_code), ctx) };
+        self.retired_cbs
+            .update::<CodeCallback>(&mut self.code_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for learn events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_learn(
+        chan: IrHandle,
+        ctx: *mut c_void,
+        code: *const c_char,
+        code_info: *mut ffi::PhidgetIR_CodeInfo,
+    ) {
+        if !ctx.is_null() {
+            let code = if code.is_null() {
+                "".into()
+            }
+            else {
+                CStr::from_ptr(code).to_string_lossy()
+            };
+            let code_info = code_info
+                .as_ref()
+                .map(IrCodeInfo::from_ffi_lossy)
+                .unwrap_or_default();
+            let cb: &mut Box<LearnCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, &code, &code_info);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive learn callbacks.
+    ///
+    /// Every learned code is delivered. If the library reports an encoding
+    /// or length that this crate doesn't know, it is passed to the handler
+    /// as `Unknown`.
+    pub fn set_on_learn_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Ir, &str, &IrCodeInfo) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<LearnCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe { ffi::PhidgetIR_setOnLearnHandler(self.chan, Some(Self::on_learn), ctx) };
+        self.retired_cbs
+            .update::<LearnCallback>(&mut self.learn_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for raw data events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_raw_data(
+        chan: IrHandle,
+        ctx: *mut c_void,
+        data: *const u32,
+        data_len: usize,
+    ) {
+        if !ctx.is_null() {
+            let data = if data.is_null() {
+                &[][..]
+            }
+            else {
+                slice::from_raw_parts(data, data_len)
+            };
+            let cb: &mut Box<RawDataCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, data);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive raw data callbacks.
+    pub fn set_on_raw_data_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Ir, &[u32]) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<RawDataCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc =
+            unsafe { ffi::PhidgetIR_setOnRawDataHandler(self.chan, Some(Self::on_raw_data), ctx) };
+        self.retired_cbs
+            .update::<RawDataCallback>(&mut self.raw_data_cb, ctx, rc)
+    }
+
+    /// Gets a stream of code events.
+    ///
+    /// This replaces any code handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn codes(&mut self) -> Result<crate::stream::EventStream<'_, IrCode>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_code_handler(move |_, code, bit_count, is_repeat| {
+            tx.send(IrCode {
+                code: code.to_string(),
+                bit_count,
+                is_repeat,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetIR_setOnCodeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs.retire::<CodeCallback>(self.code_cb.take());
+        }))
+    }
+
+    /// Gets a stream of learn events.
+    ///
+    /// This replaces any learn handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn learned_codes(&mut self) -> Result<crate::stream::EventStream<'_, IrLearnedCode>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_learn_handler(move |_, code, code_info| {
+            tx.send(IrLearnedCode {
+                code: code.to_string(),
+                code_info: code_info.clone(),
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetIR_setOnLearnHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<LearnCallback>(self.learn_cb.take());
+        }))
+    }
+
+    /// Gets a stream of raw data events.
+    ///
+    /// This replaces any raw data handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn raw_data(&mut self) -> Result<crate::stream::EventStream<'_, Vec<u32>>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_raw_data_handler(move |_, data| tx.send(data.to_vec()))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetIR_setOnRawDataHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<RawDataCallback>(self.raw_data_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any code, learn, raw data, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<IrChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_code_handler({
+            let tx = tx.clone();
+            move |_, code, bit_count, is_repeat| {
+                let _ = tx.send(Event::new(EventKind::Change(IrChange::Code(IrCode {
+                    code: code.to_string(),
+                    bit_count,
+                    is_repeat,
+                }))));
+            }
+        })?;
+        self.set_on_learn_handler({
+            let tx = tx.clone();
+            move |_, code, code_info| {
+                let _ = tx.send(Event::new(EventKind::Change(IrChange::Learn(
+                    IrLearnedCode {
+                        code: code.to_string(),
+                        code_info: code_info.clone(),
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_raw_data_handler({
+            let tx = tx.clone();
+            move |_, data| {
+                let _ = tx.send(Event::new(EventKind::Change(IrChange::RawData(
+                    data.to_vec(),
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Ir {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Ir {}
+
+impl Default for Ir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<IrHandle> for Ir {
+    fn from(chan: IrHandle) -> Self {
+        Self {
+            chan,
+            code_cb: None,
+            learn_cb: None,
+            raw_data_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Ir {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetIR_delete(&mut self.chan);
+            crate::drop_cb::<CodeCallback>(self.code_cb.take());
+            crate::drop_cb::<LearnCallback>(self.learn_cb.take());
+            crate::drop_cb::<RawDataCallback>(self.raw_data_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_info_to_ffi() {
+        let info = IrCodeInfo {
+            bit_count: 12,
+            repeat: vec![1, 2, 3],
+            toggle_mask: "0x1".into(),
+            ..IrCodeInfo::default()
+        };
+        let ffi_info = info.to_ffi().unwrap();
+        assert_eq!(ffi_info.bitCount, 12);
+        assert_eq!(&ffi_info.repeat[..4], &[1, 2, 3, 0]);
+        assert_eq!(ffi_info.toggleMask[3], 0);
+        assert_eq!(IrCodeInfo::try_from(&ffi_info), Ok(info));
+    }
+
+    #[test]
+    fn code_info_from_ffi_unknown_values() {
+        let mut ffi_info = IrCodeInfo::default().to_ffi().unwrap();
+        ffi_info.bitCount = 32;
+        ffi_info.encoding = 99;
+        ffi_info.length = 99;
+        assert!(IrCodeInfo::try_from(&ffi_info).is_err());
+
+        let info = IrCodeInfo::from_ffi_lossy(&ffi_info);
+        assert_eq!(info.bit_count, 32);
+        assert_eq!(info.encoding, IrEncoding::Unknown);
+        assert_eq!(info.length, IrLength::Unknown);
+    }
+
+    #[test]
+    fn code_info_to_ffi_bounds() {
+        let info = IrCodeInfo::default();
+        let ffi_info = info.to_ffi().unwrap();
+        let (n_repeat, n_mask) = (ffi_info.repeat.len(), ffi_info.toggleMask.len());
+
+        // The arrays must leave room for the zero terminator
+        let info = IrCodeInfo {
+            repeat: vec![1; n_repeat - 1],
+            toggle_mask: "1".repeat(n_mask - 1),
+            ..IrCodeInfo::default()
+        };
+        assert!(info.to_ffi().is_ok());
+
+        let info = IrCodeInfo {
+            repeat: vec![1; n_repeat],
+            ..IrCodeInfo::default()
+        };
+        assert_eq!(info.to_ffi().err(), Some(ReturnCode::InvalidArg));
+
+        let info = IrCodeInfo {
+            toggle_mask: "1".repeat(n_mask),
+            ..IrCodeInfo::default()
+        };
+        assert_eq!(info.to_ffi().err(), Some(ReturnCode::InvalidArg));
+    }
+}
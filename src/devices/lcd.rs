@@ -0,0 +1,717 @@
+// phidget-rs/src/lcd.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+#[cfg(feature = "embedded-graphics")]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::BinaryColor,
+    primitives::Rectangle,
+    Pixel,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetLCDHandle as LcdHandle};
+use std::{
+    convert::Infallible,
+    ffi::CString,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The fonts available on an LCD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum LcdFont {
+    /// User-defined font #1
+    User1 = ffi::PhidgetLCD_Font_FONT_User1,
+    /// User-defined font #2
+    User2 = ffi::PhidgetLCD_Font_FONT_User2,
+    /// 6x10 pixel font
+    Font6x10 = ffi::PhidgetLCD_Font_FONT_6x10,
+    /// 5x8 pixel font
+    Font5x8 = ffi::PhidgetLCD_Font_FONT_5x8,
+    /// 6x12 pixel font
+    Font6x12 = ffi::PhidgetLCD_Font_FONT_6x12,
+}
+
+impl TryFrom<u32> for LcdFont {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use LcdFont::*;
+        match val {
+            ffi::PhidgetLCD_Font_FONT_User1 => Ok(User1),
+            ffi::PhidgetLCD_Font_FONT_User2 => Ok(User2),
+            ffi::PhidgetLCD_Font_FONT_6x10 => Ok(Font6x10),
+            ffi::PhidgetLCD_Font_FONT_5x8 => Ok(Font5x8),
+            ffi::PhidgetLCD_Font_FONT_6x12 => Ok(Font6x12),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// The size of a character LCD screen, in rows x columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum LcdScreenSize {
+    /// No screen attached
+    None = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_NONE,
+    /// 1 row, 8 columns
+    Size1x8 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x8,
+    /// 2 rows, 8 columns
+    Size2x8 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x8,
+    /// 1 row, 16 columns
+    Size1x16 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x16,
+    /// 2 rows, 16 columns
+    Size2x16 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x16,
+    /// 4 rows, 16 columns
+    Size4x16 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x16,
+    /// 2 rows, 20 columns
+    Size2x20 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x20,
+    /// 4 rows, 20 columns
+    Size4x20 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x20,
+    /// 2 rows, 24 columns
+    Size2x24 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x24,
+    /// 1 row, 40 columns
+    Size1x40 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x40,
+    /// 2 rows, 40 columns
+    Size2x40 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x40,
+    /// 4 rows, 40 columns
+    Size4x40 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x40,
+    /// 64x128 pixel graphic display
+    Size64x128 = ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_64x128,
+}
+
+impl TryFrom<u32> for LcdScreenSize {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use LcdScreenSize::*;
+        match val {
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_NONE => Ok(None),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x8 => Ok(Size1x8),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x8 => Ok(Size2x8),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x16 => Ok(Size1x16),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x16 => Ok(Size2x16),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x16 => Ok(Size4x16),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x20 => Ok(Size2x20),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x20 => Ok(Size4x20),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x24 => Ok(Size2x24),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_1x40 => Ok(Size1x40),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_2x40 => Ok(Size2x40),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_4x40 => Ok(Size4x40),
+            ffi::PhidgetLCD_ScreenSize_SCREEN_SIZE_64x128 => Ok(Size64x128),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// The state to draw a pixel on an LCD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum LcdPixelState {
+    /// The pixel is off
+    Off = ffi::PhidgetLCD_PixelState_PIXEL_STATE_OFF,
+    /// The pixel is on
+    On = ffi::PhidgetLCD_PixelState_PIXEL_STATE_ON,
+    /// The pixel is toggled from its current state
+    Invert = ffi::PhidgetLCD_PixelState_PIXEL_STATE_INVERT,
+}
+
+impl TryFrom<u32> for LcdPixelState {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use LcdPixelState::*;
+        match val {
+            ffi::PhidgetLCD_PixelState_PIXEL_STATE_OFF => Ok(Off),
+            ffi::PhidgetLCD_PixelState_PIXEL_STATE_ON => Ok(On),
+            ffi::PhidgetLCD_PixelState_PIXEL_STATE_INVERT => Ok(Invert),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+// Gets the number of bytes in a bitmap of the specified size, failing
+// if either dimension is negative or the size overflows.
+fn bitmap_len(x_size: i32, y_size: i32) -> Result<usize> {
+    if x_size < 0 || y_size < 0 {
+        return Err(ReturnCode::InvalidArg);
+    }
+    x_size
+        .checked_mul(y_size)
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or(ReturnCode::InvalidArg)
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget LCD
+pub struct Lcd {
+    // Handle to the channel in the phidget22 library
+    chan: LcdHandle,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Lcd {
+    /// Create a new LCD.
+    pub fn new() -> Self {
+        let mut chan: LcdHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetLCD_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &LcdHandle {
+        &self.chan
+    }
+
+    /// Gets the backlight.
+    pub fn backlight(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getBacklight(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the backlight brightness, from 0.0 (off) to 1.0.
+    pub fn set_backlight(&self, backlight: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setBacklight(self.chan, backlight) })
+    }
+
+    /// Gets the minimum backlight.
+    pub fn min_backlight(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getMinBacklight(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum backlight.
+    pub fn max_backlight(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getMaxBacklight(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the contrast.
+    pub fn contrast(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getContrast(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the contrast of the display, from 0.0 to 1.0.
+    pub fn set_contrast(&self, contrast: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setContrast(self.chan, contrast) })
+    }
+
+    /// Gets the minimum contrast.
+    pub fn min_contrast(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getMinContrast(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum contrast.
+    pub fn max_contrast(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getMaxContrast(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Determines whether the display is flushed after every draw call.
+    pub fn auto_flush(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getAutoFlush(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Sets whether the display is flushed after every draw call.
+    /// When off, changes only appear after a call to [`flush()`](Self::flush).
+    pub fn set_auto_flush(&self, on: bool) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setAutoFlush(self.chan, c_int::from(on)) })
+    }
+
+    /// Determines whether the cursor is blinking.
+    pub fn cursor_blink(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getCursorBlink(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Turns the blinking cursor on or off.
+    pub fn set_cursor_blink(&self, on: bool) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setCursorBlink(self.chan, c_int::from(on)) })
+    }
+
+    /// Determines whether the cursor is visible.
+    pub fn cursor_on(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getCursorOn(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Shows or hides the cursor.
+    pub fn set_cursor_on(&self, on: bool) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setCursorOn(self.chan, c_int::from(on)) })
+    }
+
+    /// Gets the frame buffer that is being drawn to.
+    pub fn frame_buffer(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getFrameBuffer(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the frame buffer that subsequent draw calls will modify.
+    pub fn set_frame_buffer(&self, frame_buffer: i32) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setFrameBuffer(self.chan, frame_buffer) })
+    }
+
+    /// Gets the height of the display, in pixels.
+    pub fn height(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getHeight(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the width of the display, in pixels.
+    pub fn width(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getWidth(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the size of a character screen.
+    pub fn screen_size(&self) -> Result<LcdScreenSize> {
+        let mut value: ffi::PhidgetLCD_ScreenSize = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getScreenSize(self.chan, &mut value) })?;
+        LcdScreenSize::try_from(value)
+    }
+
+    /// Sets the size of the character screen attached to the channel.
+    pub fn set_screen_size(&self, screen_size: LcdScreenSize) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_setScreenSize(self.chan, screen_size as c_uint)
+        })
+    }
+
+    /// Determines whether the display is asleep.
+    pub fn sleeping(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_getSleeping(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Puts the display to sleep, or wakes it up. A sleeping display
+    /// turns off the backlight and stops drawing.
+    pub fn set_sleeping(&self, sleeping: bool) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_setSleeping(self.chan, c_int::from(sleeping)) })
+    }
+
+    /// Initializes a character screen. This must be called after the
+    /// screen size is set, and whenever the screen is reconnected.
+    pub fn initialize(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_initialize(self.chan) })
+    }
+
+    /// Clears all the pixels in the current frame buffer.
+    pub fn clear(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_clear(self.chan) })
+    }
+
+    /// Flushes the buffered drawing to the display.
+    pub fn flush(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_flush(self.chan) })
+    }
+
+    /// Saves a frame buffer to the device flash, so that it is shown
+    /// when the device is powered on.
+    pub fn save_frame_buffer(&self, frame_buffer: i32) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_saveFrameBuffer(self.chan, frame_buffer) })
+    }
+
+    /// Gets the size of the characters in a font, as `(width, height)` in pixels.
+    pub fn font_size(&self, font: LcdFont) -> Result<(i32, i32)> {
+        let (mut width, mut height) = (0, 0);
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_getFontSize(self.chan, font as c_uint, &mut width, &mut height)
+        })?;
+        Ok((width, height))
+    }
+
+    /// Sets the size of the characters in a user-defined font.
+    pub fn set_font_size(&self, font: LcdFont, width: i32, height: i32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_setFontSize(self.chan, font as c_uint, width, height)
+        })
+    }
+
+    /// Gets the maximum number of custom characters that can be defined
+    /// for a font.
+    pub fn max_characters(&self, font: LcdFont) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_getMaxCharacters(self.chan, font as c_uint, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Defines a custom character in a user-defined font.
+    ///
+    /// The `character` is the string that is used to draw it with
+    /// [`write_text()`](Self::write_text), and the `bitmap` has one byte
+    /// per pixel, row by row, with a non-zero value for the pixels that
+    /// are on. It must contain at least `width * height` bytes for the
+    /// size of the font.
+    pub fn set_character_bitmap(
+        &self,
+        font: LcdFont,
+        character: &str,
+        bitmap: &[u8],
+    ) -> Result<()> {
+        let (width, height) = self.font_size(font)?;
+        if bitmap.len() < bitmap_len(width, height)? {
+            return Err(ReturnCode::InvalidArg);
+        }
+        let character = CString::new(character).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_setCharacterBitmap(
+                self.chan,
+                font as c_uint,
+                character.as_ptr(),
+                bitmap.as_ptr(),
+            )
+        })
+    }
+
+    /// Writes text to the display, starting at the specified position.
+    ///
+    /// For character displays, the position is in characters. For graphic
+    /// displays, it is in pixels.
+    pub fn write_text(&self, font: LcdFont, x: i32, y: i32, text: &str) -> Result<()> {
+        let text = CString::new(text).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_writeText(self.chan, font as c_uint, x, y, text.as_ptr())
+        })
+    }
+
+    /// Draws a bitmap to the display, with its top-left corner at the
+    /// specified position.
+    ///
+    /// The `bitmap` has one byte per pixel, row by row, with a non-zero
+    /// value for the pixels that are on. It must contain at least
+    /// `x_size * y_size` bytes.
+    pub fn write_bitmap(
+        &self,
+        x: i32,
+        y: i32,
+        x_size: i32,
+        y_size: i32,
+        bitmap: &[u8],
+    ) -> Result<()> {
+        if bitmap.len() < bitmap_len(x_size, y_size)? {
+            return Err(ReturnCode::InvalidArg);
+        }
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_writeBitmap(self.chan, x, y, x_size, y_size, bitmap.as_ptr())
+        })
+    }
+
+    /// Draws a single pixel.
+    pub fn draw_pixel(&self, x: i32, y: i32, state: LcdPixelState) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_drawPixel(self.chan, x, y, state as c_uint) })
+    }
+
+    /// Draws a line between two points.
+    pub fn draw_line(&self, x1: i32, y1: i32, x2: i32, y2: i32) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetLCD_drawLine(self.chan, x1, y1, x2, y2) })
+    }
+
+    /// Draws a rectangle with the specified corners.
+    ///
+    /// When `filled` is set, the whole area of the rectangle is drawn,
+    /// otherwise just the outline. When `inverted` is set, the pixels are
+    /// turned off rather than on.
+    pub fn draw_rect(
+        &self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        filled: bool,
+        inverted: bool,
+    ) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_drawRect(
+                self.chan,
+                x1,
+                y1,
+                x2,
+                y2,
+                c_int::from(filled),
+                c_int::from(inverted),
+            )
+        })
+    }
+
+    /// Copies an area from one frame buffer to another.
+    ///
+    /// The `source` area is given by its corners as `(x1, y1, x2, y2)`,
+    /// and `dest` is the position of its top-left corner in the destination.
+    /// When `inverted` is set, the pixels are inverted as they are copied.
+    pub fn copy(
+        &self,
+        source_frame_buffer: i32,
+        dest_frame_buffer: i32,
+        source: (i32, i32, i32, i32),
+        dest: (i32, i32),
+        inverted: bool,
+    ) -> Result<()> {
+        let (x1, y1, x2, y2) = source;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLCD_copy(
+                self.chan,
+                source_frame_buffer,
+                dest_frame_buffer,
+                x1,
+                y1,
+                x2,
+                y2,
+                dest.0,
+                dest.1,
+                c_int::from(inverted),
+            )
+        })
+    }
+
+    /// Gets an adapter to draw on a graphic display with the
+    /// `embedded-graphics` crate.
+    ///
+    /// The display must be attached, as its size is read when the
+    /// adapter is created.
+    #[cfg(feature = "embedded-graphics")]
+    pub fn display(&self) -> Result<LcdDisplay<'_>> {
+        let width = self.width()?;
+        let height = self.height()?;
+        Ok(LcdDisplay {
+            lcd: self,
+            size: Size::new(width as u32, height as u32),
+        })
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any attach, detach, and error handlers that were
+    /// previously set, and returns a receiver for the events. The channel
+    /// has no value change events of its own.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<Infallible>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Lcd {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Lcd {}
+
+impl Default for Lcd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<LcdHandle> for Lcd {
+    fn from(chan: LcdHandle) -> Self {
+        Self {
+            chan,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Lcd {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetLCD_delete(&mut self.chan);
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// An adapter to draw on a graphic LCD with the `embedded-graphics` crate.
+///
+/// Drawing goes to the current frame buffer of the LCD. Unless auto-flush
+/// is enabled, call [`Lcd::flush()`] to show the result.
+#[cfg(feature = "embedded-graphics")]
+pub struct LcdDisplay<'a> {
+    // The LCD to draw on
+    lcd: &'a Lcd,
+    // The size of the display, in pixels
+    size: Size,
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl OriginDimensions for LcdDisplay<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl DrawTarget for LcdDisplay<'_> {
+    type Color = BinaryColor;
+    type Error = Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        for Pixel(pt, color) in pixels {
+            if area.contains(pt) {
+                let state = if color.is_on() {
+                    LcdPixelState::On
+                }
+                else {
+                    LcdPixelState::Off
+                };
+                self.lcd.draw_pixel(pt.x, pt.y, state)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<()> {
+        let area = area.intersection(&self.bounding_box());
+        if let Some(bottom_right) = area.bottom_right() {
+            let top_left = area.top_left;
+            self.lcd.draw_rect(
+                top_left.x,
+                top_left.y,
+                bottom_right.x,
+                bottom_right.y,
+                true,
+                color.is_off(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<()> {
+        let area = self.bounding_box();
+        self.fill_solid(&area, color)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitmap_len_normal_size() {
+        assert_eq!(bitmap_len(128, 64), Ok(8192));
+        assert_eq!(bitmap_len(5, 8), Ok(40));
+        assert_eq!(bitmap_len(0, 64), Ok(0));
+    }
+
+    #[test]
+    fn bitmap_len_invalid_size() {
+        assert_eq!(bitmap_len(-1, 8), Err(ReturnCode::InvalidArg));
+        assert_eq!(bitmap_len(8, -1), Err(ReturnCode::InvalidArg));
+        assert_eq!(bitmap_len(i32::MAX, 2), Err(ReturnCode::InvalidArg));
+        assert_eq!(bitmap_len(65536, 65536), Err(ReturnCode::InvalidArg));
+    }
+}
@@ -0,0 +1,284 @@
+// phidget-rs/src/light_sensor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetLightSensorHandle as LightSensorHandle};
+use std::{mem, os::raw::c_void, ptr};
+
+/// The function type for the safe Rust illuminance change callback.
+pub type IlluminanceChangeCallback = dyn Fn(&LightSensor, f64) + Send + 'static;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget light sensor
+pub struct LightSensor {
+    // Handle to the channel in the phidget22 library
+    chan: LightSensorHandle,
+    // Double-boxed IlluminanceChangeCallback, if registered
+    illuminance_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl LightSensor {
+    /// Create a new light sensor.
+    pub fn new() -> Self {
+        let mut chan: LightSensorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetLightSensor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &LightSensorHandle {
+        &self.chan
+    }
+
+    /// Gets the measured illuminance, in lux.
+    pub fn illuminance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getIlluminance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum illuminance that can be measured, in lux.
+    pub fn min_illuminance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getMinIlluminance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum illuminance that can be measured, in lux.
+    pub fn max_illuminance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getMaxIlluminance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the illuminance change trigger.
+    pub fn illuminance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getIlluminanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in illuminance, in lux, that will fire
+    /// an illuminance change event.
+    pub fn set_illuminance_change_trigger(&self, illuminance_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_setIlluminanceChangeTrigger(
+                self.chan,
+                illuminance_change_trigger,
+            )
+        })
+    }
+
+    /// Gets the minimum illuminance change trigger.
+    pub fn min_illuminance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getMinIlluminanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum illuminance change trigger.
+    pub fn max_illuminance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetLightSensor_getMaxIlluminanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for illuminance change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_illuminance_change(
+        chan: LightSensorHandle,
+        ctx: *mut c_void,
+        illuminance: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<IlluminanceChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, illuminance);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive illuminance change callbacks.
+    pub fn set_on_illuminance_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&LightSensor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<IlluminanceChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetLightSensor_setOnIlluminanceChangeHandler(
+                self.chan,
+                Some(Self::on_illuminance_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<IlluminanceChangeCallback>(
+            &mut self.illuminance_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of illuminance change events.
+    ///
+    /// This replaces any illuminance change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn illuminance_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_illuminance_change_handler(move |_, illuminance| tx.send(illuminance))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetLightSensor_setOnIlluminanceChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<IlluminanceChangeCallback>(self.illuminance_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any illuminance change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_illuminance_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for LightSensor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for LightSensor {}
+
+impl Default for LightSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<LightSensorHandle> for LightSensor {
+    fn from(chan: LightSensorHandle) -> Self {
+        Self {
+            chan,
+            illuminance_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for LightSensor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetLightSensor_delete(&mut self.chan);
+            crate::drop_cb::<IlluminanceChangeCallback>(self.illuminance_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,398 @@
+// phidget-rs/src/magnetometer.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    devices::spatial::MagnetometerCorrection,
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetMagnetometerHandle as MagnetometerHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust magnetic field change callback.
+pub type MagneticFieldChangeCallback = dyn Fn(&Magnetometer, [f64; 3], f64) + Send + 'static;
+
+/// A magnetic field reading from a magnetometer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MagneticFieldChange {
+    /// The magnetic field for each axis, in gauss
+    pub magnetic_field: [f64; 3],
+    /// The timestamp of the reading, in milliseconds
+    pub timestamp: f64,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget magnetometer
+pub struct Magnetometer {
+    // Handle to the channel in the phidget22 library
+    chan: MagnetometerHandle,
+    // Double-boxed MagneticFieldChangeCallback, if registered
+    magnetic_field_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Magnetometer {
+    /// Create a new magnetometer.
+    pub fn new() -> Self {
+        let mut chan: MagnetometerHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetMagnetometer_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &MagnetometerHandle {
+        &self.chan
+    }
+
+    /// Gets the magnetic field for each axis, in gauss.
+    pub fn magnetic_field(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMagneticField(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum magnetic field for each axis, in gauss.
+    pub fn min_magnetic_field(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMinMagneticField(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum magnetic field for each axis, in gauss.
+    pub fn max_magnetic_field(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMaxMagneticField(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the magnetic field change trigger.
+    pub fn magnetic_field_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMagneticFieldChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in magnetic field, in gauss, that will
+    /// fire a magnetic field change event.
+    pub fn set_magnetic_field_change_trigger(
+        &self,
+        magnetic_field_change_trigger: f64,
+    ) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_setMagneticFieldChangeTrigger(
+                self.chan,
+                magnetic_field_change_trigger,
+            )
+        })
+    }
+
+    /// Gets the minimum magnetic field change trigger.
+    pub fn min_magnetic_field_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMinMagneticFieldChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum magnetic field change trigger.
+    pub fn max_magnetic_field_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getMaxMagneticFieldChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the calibration parameters.
+    ///
+    /// These take effect immediately, but are lost when the device is
+    /// powered off unless they are saved with
+    /// [`save_correction_parameters()`](Self::save_correction_parameters).
+    pub fn set_correction_parameters(&self, params: &MagnetometerCorrection) -> Result<()> {
+        let MagnetometerCorrection {
+            magnetic_field,
+            offset,
+            gain,
+            t,
+        } = *params;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_setCorrectionParameters(
+                self.chan,
+                magnetic_field,
+                offset[0],
+                offset[1],
+                offset[2],
+                gain[0],
+                gain[1],
+                gain[2],
+                t[0],
+                t[1],
+                t[2],
+                t[3],
+                t[4],
+                t[5],
+            )
+        })
+    }
+
+    /// Resets the calibration parameters to their defaults.
+    pub fn reset_correction_parameters(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMagnetometer_resetCorrectionParameters(self.chan) })
+    }
+
+    /// Saves the calibration parameters to the device flash.
+    pub fn save_correction_parameters(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMagnetometer_saveCorrectionParameters(self.chan) })
+    }
+
+    /// Gets the number of axes the magnetometer can measure.
+    pub fn axis_count(&self) -> Result<i32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getAxisCount(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines whether the internal heater is enabled.
+    pub fn heating_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getHeatingEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the internal heater, which keeps the sensor
+    /// at a stable temperature.
+    pub fn set_heating_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_setHeatingEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Gets the timestamp of the most recent reading, in milliseconds.
+    pub fn timestamp(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMagnetometer_getTimestamp(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for magnetic field change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_magnetic_field_change(
+        chan: MagnetometerHandle,
+        ctx: *mut c_void,
+        magnetic_field: *const f64,
+        timestamp: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<MagneticFieldChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, *(magnetic_field as *const [f64; 3]), timestamp);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive magnetic field change callbacks.
+    pub fn set_on_magnetic_field_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Magnetometer, [f64; 3], f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<MagneticFieldChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetMagnetometer_setOnMagneticFieldChangeHandler(
+                self.chan,
+                Some(Self::on_magnetic_field_change),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<MagneticFieldChangeCallback>(
+            &mut self.magnetic_field_change_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of magnetic field change events.
+    ///
+    /// This replaces any magnetic field change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn magnetic_field_changes(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, MagneticFieldChange>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_magnetic_field_change_handler(move |_, magnetic_field, timestamp| {
+            tx.send(MagneticFieldChange {
+                magnetic_field,
+                timestamp,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetMagnetometer_setOnMagneticFieldChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<MagneticFieldChangeCallback>(self.magnetic_field_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any magnetic field change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<MagneticFieldChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_magnetic_field_change_handler({
+            let tx = tx.clone();
+            move |_, magnetic_field, timestamp| {
+                let _ = tx.send(Event::new(EventKind::Change(MagneticFieldChange {
+                    magnetic_field,
+                    timestamp,
+                })));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Magnetometer {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Magnetometer {}
+
+impl Default for Magnetometer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<MagnetometerHandle> for Magnetometer {
+    fn from(chan: MagnetometerHandle) -> Self {
+        Self {
+            chan,
+            magnetic_field_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Magnetometer {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetMagnetometer_delete(&mut self.chan);
+            crate::drop_cb::<MagneticFieldChangeCallback>(self.magnetic_field_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
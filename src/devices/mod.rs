@@ -1,3 +1,47 @@
+/// Phidget accelerometer
+pub mod accelerometer;
+pub use crate::devices::accelerometer::Accelerometer;
+
+/// Phidget brushless DC motor controller
+pub mod bldc_motor;
+pub use crate::devices::bldc_motor::BldcMotor;
+
+/// Phidget capacitive touch sensor
+pub mod capacitive_touch;
+pub use crate::devices::capacitive_touch::CapacitiveTouch;
+
+/// Phidget current input
+pub mod current_input;
+pub use crate::devices::current_input::CurrentInput;
+
+/// Phidget dictionary
+pub mod dictionary;
+pub use crate::devices::dictionary::Dictionary;
+
+/// Phidget distance sensor
+pub mod distance_sensor;
+pub use crate::devices::distance_sensor::DistanceSensor;
+
+/// Phidget DC motor controller
+pub mod dc_motor;
+pub use crate::devices::dc_motor::DcMotor;
+
+/// Phidget encoder
+pub mod encoder;
+pub use crate::devices::encoder::Encoder;
+
+/// Phidget frequency counter
+pub mod frequency_counter;
+pub use crate::devices::frequency_counter::FrequencyCounter;
+
+/// Phidget GPS receiver
+pub mod gps;
+pub use crate::devices::gps::Gps;
+
+/// Phidget gyroscope
+pub mod gyroscope;
+pub use crate::devices::gyroscope::Gyroscope;
+
 /// Phidget hub
 pub mod hub;
 pub use crate::devices::hub::{Hub, HubPortMode};
@@ -6,6 +50,58 @@ pub use crate::devices::hub::{Hub, HubPortMode};
 pub mod humidity_sensor;
 pub use crate::devices::humidity_sensor::HumiditySensor;
 
+/// Phidget IR transmitter/receiver
+pub mod ir;
+pub use crate::devices::ir::Ir;
+
+/// Phidget light sensor
+pub mod light_sensor;
+pub use crate::devices::light_sensor::LightSensor;
+
+/// Phidget LCD
+pub mod lcd;
+pub use crate::devices::lcd::Lcd;
+
+/// Phidget magnetometer
+pub mod magnetometer;
+pub use crate::devices::magnetometer::Magnetometer;
+
+/// Phidget motor position controller
+pub mod motor_position_controller;
+pub use crate::devices::motor_position_controller::MotorPositionController;
+
+/// Phidget pH sensor
+pub mod ph_sensor;
+pub use crate::devices::ph_sensor::PhSensor;
+
+/// Phidget power guard
+pub mod power_guard;
+pub use crate::devices::power_guard::PowerGuard;
+
+/// Phidget pressure sensor
+pub mod pressure_sensor;
+pub use crate::devices::pressure_sensor::PressureSensor;
+
+/// Phidget RC servo
+pub mod rc_servo;
+pub use crate::devices::rc_servo::RcServo;
+
+/// Phidget resistance input
+pub mod resistance_input;
+pub use crate::devices::resistance_input::ResistanceInput;
+
+/// Phidget RFID reader
+pub mod rfid;
+pub use crate::devices::rfid::Rfid;
+
+/// Phidget sound sensor
+pub mod sound_sensor;
+pub use crate::devices::sound_sensor::SoundSensor;
+
+/// Phidget spatial (IMU)
+pub mod spatial;
+pub use crate::devices::spatial::Spatial;
+
 /// Phidget stepper
 pub mod stepper;
 pub use crate::devices::stepper::Stepper;
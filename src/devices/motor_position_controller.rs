@@ -0,0 +1,710 @@
+// phidget-rs/src/motor_position_controller.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    devices::{dc_motor::FanMode, encoder::EncoderIoMode},
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetHandle,
+    PhidgetMotorPositionControllerHandle as MotorPositionControllerHandle,
+};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust position change callback.
+pub type PositionChangeCallback = dyn Fn(&MotorPositionController, f64) + Send + 'static;
+/// The function type for the safe Rust duty cycle update callback.
+pub type DutyCycleUpdateCallback = dyn Fn(&MotorPositionController, f64) + Send + 'static;
+
+/// The value changes reported by a motor position controller through `MotorPositionController::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotorPositionControllerChange {
+    /// Position change
+    Position(f64),
+    /// Duty cycle update
+    DutyCycle(f64),
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget motor position controller
+///
+/// The expected position getters are not wrapped, since the bundled
+/// phidget22 bindings predate them.
+pub struct MotorPositionController {
+    // Handle to the channel in the phidget22 library
+    chan: MotorPositionControllerHandle,
+    // Double-boxed PositionChangeCallback, if registered
+    position_change_cb: Option<*mut c_void>,
+    // Double-boxed DutyCycleUpdateCallback, if registered
+    duty_cycle_update_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl MotorPositionController {
+    /// Create a new motor position controller.
+    pub fn new() -> Self {
+        let mut chan: MotorPositionControllerHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetMotorPositionController_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &MotorPositionControllerHandle {
+        &self.chan
+    }
+
+    /// Enables the failsafe feature for the channel, with the specified
+    /// failsafe time, in milliseconds.
+    pub fn set_enable_failsafe(&self, failsafe_time: u32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_enableFailsafe(self.chan, failsafe_time)
+        })
+    }
+
+    /// Resets the failsafe timer, if one has been set.
+    pub fn set_reset_failsafe(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMotorPositionController_resetFailsafe(self.chan) })
+    }
+
+    /// Gets the minimum failsafe time, in milliseconds.
+    pub fn min_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum failsafe time, in milliseconds.
+    pub fn max_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the acceleration.
+    pub fn acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the acceleration.
+    pub fn set_acceleration(&self, acceleration: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setAcceleration(self.chan, acceleration)
+        })
+    }
+
+    /// Gets the minimum acceleration.
+    pub fn min_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration.
+    pub fn max_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Adds an offset, in rescaled units, to the position. This is useful
+    /// for zeroing the position.
+    pub fn add_position_offset(&self, offset: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_addPositionOffset(self.chan, offset)
+        })
+    }
+
+    /// Gets the current limit, in amps.
+    pub fn current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the current limit, in amps.
+    pub fn set_current_limit(&self, current_limit: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setCurrentLimit(self.chan, current_limit)
+        })
+    }
+
+    /// Gets the minimum current limit, in amps.
+    pub fn min_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current limit, in amps.
+    pub fn max_current_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxCurrentLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the position of the motor, in rescaled units.
+    pub fn position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getPosition(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum position.
+    pub fn min_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinPosition(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum position.
+    pub fn max_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxPosition(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the rescale factor.
+    pub fn rescale_factor(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getRescaleFactor(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the rescale factor, which converts the position from
+    /// commutation steps into user units.
+    pub fn set_rescale_factor(&self, rescale_factor: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setRescaleFactor(self.chan, rescale_factor)
+        })
+    }
+
+    /// Gets the stall velocity.
+    pub fn stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the velocity, in rescaled units per second, below which the
+    /// motor is considered stalled. Zero disables stall protection.
+    pub fn set_stall_velocity(&self, stall_velocity: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setStallVelocity(self.chan, stall_velocity)
+        })
+    }
+
+    /// Gets the minimum stall velocity.
+    pub fn min_stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum stall velocity.
+    pub fn max_stall_velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxStallVelocity(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the current regulator gain.
+    pub fn current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the current regulator gain.
+    pub fn set_current_regulator_gain(&self, current_regulator_gain: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setCurrentRegulatorGain(
+                self.chan,
+                current_regulator_gain,
+            )
+        })
+    }
+
+    /// Gets the minimum current regulator gain.
+    pub fn min_current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum current regulator gain.
+    pub fn max_current_regulator_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxCurrentRegulatorGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the dead band, in rescaled units.
+    pub fn dead_band(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getDeadBand(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the dead band, in rescaled units. The controller will not try
+    /// to correct the position while it is within the dead band of the target.
+    pub fn set_dead_band(&self, dead_band: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setDeadBand(self.chan, dead_band)
+        })
+    }
+
+    /// Gets the duty cycle currently applied to the motor.
+    pub fn duty_cycle(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getDutyCycle(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines if the controller is engaged.
+    pub fn engaged(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getEngaged(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Engages or disengages the controller. When engaged, the controller
+    /// drives the motor toward the target position.
+    pub fn set_engaged(&self, engaged: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setEngaged(self.chan, c_int::from(engaged))
+        })
+    }
+
+    /// Gets the fan mode.
+    pub fn fan_mode(&self) -> Result<FanMode> {
+        let mut value: ffi::Phidget_FanMode = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getFanMode(self.chan, &mut value)
+        })?;
+        FanMode::try_from(value)
+    }
+
+    /// Sets the fan mode.
+    pub fn set_fan_mode(&self, fan_mode: FanMode) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setFanMode(self.chan, fan_mode as c_uint)
+        })
+    }
+
+    /// Gets the IO mode of the encoder input.
+    pub fn io_mode(&self) -> Result<EncoderIoMode> {
+        let mut value: ffi::Phidget_EncoderIOMode = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getIOMode(self.chan, &mut value)
+        })?;
+        EncoderIoMode::try_from(value)
+    }
+
+    /// Sets the IO mode of the encoder input to match the encoder outputs.
+    pub fn set_io_mode(&self, io_mode: EncoderIoMode) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setIOMode(self.chan, io_mode as c_uint)
+        })
+    }
+
+    /// Gets the derivative gain of the PID controller.
+    pub fn kd(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getKd(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the derivative gain of the PID controller.
+    pub fn set_kd(&self, kd: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMotorPositionController_setKd(self.chan, kd) })
+    }
+
+    /// Gets the integral gain of the PID controller.
+    pub fn ki(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getKi(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the integral gain of the PID controller.
+    pub fn set_ki(&self, ki: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMotorPositionController_setKi(self.chan, ki) })
+    }
+
+    /// Gets the proportional gain of the PID controller.
+    pub fn kp(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getKp(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the proportional gain of the PID controller.
+    pub fn set_kp(&self, kp: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetMotorPositionController_setKp(self.chan, kp) })
+    }
+
+    /// Gets the target position, in rescaled units.
+    pub fn target_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getTargetPosition(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the position, in rescaled units, that the controller will
+    /// drive the motor toward.
+    pub fn set_target_position(&self, target_position: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setTargetPosition(self.chan, target_position)
+        })
+    }
+
+    /// Gets the velocity limit.
+    pub fn velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getVelocityLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the maximum velocity, in rescaled units per second, at which
+    /// the motor is driven toward the target position.
+    pub fn set_velocity_limit(&self, velocity_limit: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_setVelocityLimit(self.chan, velocity_limit)
+        })
+    }
+
+    /// Gets the minimum velocity limit.
+    pub fn min_velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMinVelocityLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum velocity limit.
+    pub fn max_velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetMotorPositionController_getMaxVelocityLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for position change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_change(
+        chan: MotorPositionControllerHandle,
+        ctx: *mut c_void,
+        position: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position change callbacks.
+    pub fn set_on_position_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&MotorPositionController, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetMotorPositionController_setOnPositionChangeHandler(
+                self.chan,
+                Some(Self::on_position_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for duty cycle update events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_duty_cycle_update(
+        chan: MotorPositionControllerHandle,
+        ctx: *mut c_void,
+        duty_cycle: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<DutyCycleUpdateCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, duty_cycle);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive duty cycle update callbacks.
+    pub fn set_on_duty_cycle_update_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&MotorPositionController, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<DutyCycleUpdateCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetMotorPositionController_setOnDutyCycleUpdateHandler(
+                self.chan,
+                Some(Self::on_duty_cycle_update),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<DutyCycleUpdateCallback>(&mut self.duty_cycle_update_cb, ctx, rc)
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(move |_, position| tx.send(position))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetMotorPositionController_setOnPositionChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of duty cycle update events.
+    ///
+    /// This replaces any duty cycle update handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn duty_cycle_updates(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_duty_cycle_update_handler(move |_, duty_cycle| tx.send(duty_cycle))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetMotorPositionController_setOnDutyCycleUpdateHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<DutyCycleUpdateCallback>(self.duty_cycle_update_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any position change, duty cycle update, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<MotorPositionControllerChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, position| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    MotorPositionControllerChange::Position(position),
+                )));
+            }
+        })?;
+        self.set_on_duty_cycle_update_handler({
+            let tx = tx.clone();
+            move |_, duty_cycle| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    MotorPositionControllerChange::DutyCycle(duty_cycle),
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for MotorPositionController {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for MotorPositionController {}
+
+impl Default for MotorPositionController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<MotorPositionControllerHandle> for MotorPositionController {
+    fn from(chan: MotorPositionControllerHandle) -> Self {
+        Self {
+            chan,
+            position_change_cb: None,
+            duty_cycle_update_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for MotorPositionController {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetMotorPositionController_delete(&mut self.chan);
+            crate::drop_cb::<PositionChangeCallback>(self.position_change_cb.take());
+            crate::drop_cb::<DutyCycleUpdateCallback>(self.duty_cycle_update_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,298 @@
+// phidget-rs/src/ph_sensor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetPHSensorHandle as PhSensorHandle};
+use std::{mem, os::raw::c_void, ptr};
+
+/// The function type for the safe Rust pH change callback.
+pub type PhChangeCallback = dyn Fn(&PhSensor, f64) + Send + 'static;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget pH sensor
+pub struct PhSensor {
+    // Handle to the channel in the phidget22 library
+    chan: PhSensorHandle,
+    // Double-boxed PhChangeCallback, if registered
+    ph_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl PhSensor {
+    /// Create a new pH sensor.
+    pub fn new() -> Self {
+        let mut chan: PhSensorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetPHSensor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &PhSensorHandle {
+        &self.chan
+    }
+
+    /// Gets the measured pH.
+    pub fn ph(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetPHSensor_getPH(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum pH that can be measured.
+    pub fn min_ph(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetPHSensor_getMinPH(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum pH that can be measured.
+    pub fn max_ph(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetPHSensor_getMaxPH(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the pH change trigger.
+    pub fn ph_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getPHChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in pH that will fire a pH change event.
+    pub fn set_ph_change_trigger(&self, ph_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_setPHChangeTrigger(self.chan, ph_change_trigger)
+        })
+    }
+
+    /// Gets the minimum pH change trigger.
+    pub fn min_ph_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getMinPHChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum pH change trigger.
+    pub fn max_ph_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getMaxPHChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the temperature, in °C, used to compensate the pH reading.
+    pub fn correction_temperature(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getCorrectionTemperature(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the temperature of the solution, in °C, to compensate the
+    /// pH reading.
+    pub fn set_correction_temperature(&self, correction_temperature: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_setCorrectionTemperature(self.chan, correction_temperature)
+        })
+    }
+
+    /// Gets the minimum correction temperature, in °C.
+    pub fn min_correction_temperature(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getMinCorrectionTemperature(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum correction temperature, in °C.
+    pub fn max_correction_temperature(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPHSensor_getMaxCorrectionTemperature(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for pH change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_ph_change(chan: PhSensorHandle, ctx: *mut c_void, ph: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PhChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, ph);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive pH change callbacks.
+    pub fn set_on_ph_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&PhSensor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PhChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetPHSensor_setOnPHChangeHandler(self.chan, Some(Self::on_ph_change), ctx)
+        };
+        self.retired_cbs
+            .update::<PhChangeCallback>(&mut self.ph_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of pH change events.
+    ///
+    /// This replaces any pH change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn ph_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_ph_change_handler(move |_, ph| tx.send(ph))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetPHSensor_setOnPHChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PhChangeCallback>(self.ph_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any pH change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_ph_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for PhSensor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for PhSensor {}
+
+impl Default for PhSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<PhSensorHandle> for PhSensor {
+    fn from(chan: PhSensorHandle) -> Self {
+        Self {
+            chan,
+            ph_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for PhSensor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetPHSensor_delete(&mut self.chan);
+            crate::drop_cb::<PhChangeCallback>(self.ph_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
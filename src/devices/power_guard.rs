@@ -0,0 +1,257 @@
+// phidget-rs/src/power_guard.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    devices::dc_motor::FanMode,
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetPowerGuardHandle as PowerGuardHandle};
+use std::{
+    convert::Infallible,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget power guard
+pub struct PowerGuard {
+    // Handle to the channel in the phidget22 library
+    chan: PowerGuardHandle,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl PowerGuard {
+    /// Create a new power guard.
+    pub fn new() -> Self {
+        let mut chan: PowerGuardHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetPowerGuard_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &PowerGuardHandle {
+        &self.chan
+    }
+
+    /// Enables the failsafe feature for the channel, with the specified
+    /// failsafe time, in milliseconds.
+    pub fn set_enable_failsafe(&self, failsafe_time: u32) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_enableFailsafe(self.chan, failsafe_time)
+        })
+    }
+
+    /// Resets the failsafe timer, if one has been set.
+    pub fn set_reset_failsafe(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetPowerGuard_resetFailsafe(self.chan) })
+    }
+
+    /// Gets the minimum failsafe time, in milliseconds.
+    pub fn min_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getMinFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum failsafe time, in milliseconds.
+    pub fn max_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getMaxFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the fan mode.
+    pub fn fan_mode(&self) -> Result<FanMode> {
+        let mut value: ffi::Phidget_FanMode = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetPowerGuard_getFanMode(self.chan, &mut value) })?;
+        FanMode::try_from(value)
+    }
+
+    /// Sets the fan mode.
+    pub fn set_fan_mode(&self, fan_mode: FanMode) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_setFanMode(self.chan, fan_mode as c_uint)
+        })
+    }
+
+    /// Gets the over-voltage level, in volts.
+    pub fn over_voltage(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getOverVoltage(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the over-voltage level, in volts. Power to the output is
+    /// disconnected when the supply voltage goes above this level.
+    pub fn set_over_voltage(&self, over_voltage: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_setOverVoltage(self.chan, over_voltage)
+        })
+    }
+
+    /// Gets the minimum over-voltage level, in volts.
+    pub fn min_over_voltage(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getMinOverVoltage(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum over-voltage level, in volts.
+    pub fn max_over_voltage(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getMaxOverVoltage(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines if power to the output is enabled.
+    pub fn power_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_getPowerEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables power to the output.
+    pub fn set_power_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPowerGuard_setPowerEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any attach, detach, and error handlers that were
+    /// previously set, and returns a receiver for the events. The channel
+    /// has no value change events of its own.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<Infallible>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for PowerGuard {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for PowerGuard {}
+
+impl Default for PowerGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<PowerGuardHandle> for PowerGuard {
+    fn from(chan: PowerGuardHandle) -> Self {
+        Self {
+            chan,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for PowerGuard {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetPowerGuard_delete(&mut self.chan);
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,280 @@
+// phidget-rs/src/pressure_sensor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetHandle, PhidgetPressureSensorHandle as PressureSensorHandle,
+};
+use std::{mem, os::raw::c_void, ptr};
+
+/// The function type for the safe Rust pressure change callback.
+pub type PressureChangeCallback = dyn Fn(&PressureSensor, f64) + Send + 'static;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget pressure sensor
+pub struct PressureSensor {
+    // Handle to the channel in the phidget22 library
+    chan: PressureSensorHandle,
+    // Double-boxed PressureChangeCallback, if registered
+    pressure_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl PressureSensor {
+    /// Create a new pressure sensor.
+    pub fn new() -> Self {
+        let mut chan: PressureSensorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetPressureSensor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &PressureSensorHandle {
+        &self.chan
+    }
+
+    /// Gets the measured pressure, in kPa.
+    pub fn pressure(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getPressure(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum pressure that can be measured, in kPa.
+    pub fn min_pressure(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getMinPressure(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum pressure that can be measured, in kPa.
+    pub fn max_pressure(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getMaxPressure(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the pressure change trigger.
+    pub fn pressure_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getPressureChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in pressure, in kPa, that will fire
+    /// a pressure change event.
+    pub fn set_pressure_change_trigger(&self, pressure_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_setPressureChangeTrigger(self.chan, pressure_change_trigger)
+        })
+    }
+
+    /// Gets the minimum pressure change trigger.
+    pub fn min_pressure_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getMinPressureChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum pressure change trigger.
+    pub fn max_pressure_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetPressureSensor_getMaxPressureChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    // Low-level, unsafe, callback for pressure change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_pressure_change(
+        chan: PressureSensorHandle,
+        ctx: *mut c_void,
+        pressure: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PressureChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, pressure);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive pressure change callbacks.
+    pub fn set_on_pressure_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&PressureSensor, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PressureChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetPressureSensor_setOnPressureChangeHandler(
+                self.chan,
+                Some(Self::on_pressure_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PressureChangeCallback>(&mut self.pressure_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of pressure change events.
+    ///
+    /// This replaces any pressure change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn pressure_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_pressure_change_handler(move |_, pressure| tx.send(pressure))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetPressureSensor_setOnPressureChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PressureChangeCallback>(self.pressure_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any pressure change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_pressure_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for PressureSensor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for PressureSensor {}
+
+impl Default for PressureSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<PressureSensorHandle> for PressureSensor {
+    fn from(chan: PressureSensorHandle) -> Self {
+        Self {
+            chan,
+            pressure_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for PressureSensor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetPressureSensor_delete(&mut self.chan);
+            crate::drop_cb::<PressureChangeCallback>(self.pressure_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,662 @@
+// phidget-rs/src/rc_servo.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetRCServoHandle as RcServoHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust position change callback.
+pub type PositionChangeCallback = dyn Fn(&RcServo, f64) + Send + 'static;
+/// The function type for the safe Rust velocity change callback.
+pub type VelocityChangeCallback = dyn Fn(&RcServo, f64) + Send + 'static;
+/// The function type for the safe Rust target position reached callback.
+pub type TargetPositionReachedCallback = dyn Fn(&RcServo, f64) + Send + 'static;
+
+/// The value changes reported by an RC servo through `RcServo::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RcServoChange {
+    /// Position change
+    Position(f64),
+    /// Velocity change
+    Velocity(f64),
+    /// Target position reached
+    TargetPositionReached(f64),
+}
+
+/// The supply voltage for an RC servo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum RcServoVoltage {
+    /// 5.0V
+    Volts5_0 = ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_5V,
+    /// 6.0V
+    Volts6_0 = ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_6V,
+    /// 7.4V
+    Volts7_4 = ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_7_4V,
+}
+
+impl TryFrom<u32> for RcServoVoltage {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use RcServoVoltage::*;
+        match val {
+            ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_5V => Ok(Volts5_0),
+            ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_6V => Ok(Volts6_0),
+            ffi::PhidgetRCServo_Voltage_RCSERVO_VOLTAGE_7_4V => Ok(Volts7_4),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget RC servo
+pub struct RcServo {
+    // Handle to the channel in the phidget22 library
+    chan: RcServoHandle,
+    // Double-boxed PositionChangeCallback, if registered
+    position_change_cb: Option<*mut c_void>,
+    // Double-boxed VelocityChangeCallback, if registered
+    velocity_change_cb: Option<*mut c_void>,
+    // Double-boxed TargetPositionReachedCallback, if registered
+    target_position_reached_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl RcServo {
+    /// Create a new RC servo.
+    pub fn new() -> Self {
+        let mut chan: RcServoHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetRCServo_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &RcServoHandle {
+        &self.chan
+    }
+
+    /// Enables the failsafe feature for the channel, with the specified
+    /// failsafe time, in milliseconds.
+    pub fn set_enable_failsafe(&self, failsafe_time: u32) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_enableFailsafe(self.chan, failsafe_time) })
+    }
+
+    /// Resets the failsafe timer, if one has been set.
+    pub fn set_reset_failsafe(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_resetFailsafe(self.chan) })
+    }
+
+    /// Gets the minimum failsafe time, in milliseconds.
+    pub fn min_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMinFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum failsafe time, in milliseconds.
+    pub fn max_failsafe_time(&self) -> Result<u32> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMaxFailsafeTime(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the acceleration.
+    pub fn acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getAcceleration(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the acceleration.
+    pub fn set_acceleration(&self, acceleration: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_setAcceleration(self.chan, acceleration) })
+    }
+
+    /// Gets the minimum acceleration.
+    pub fn min_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration.
+    pub fn max_acceleration(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines whether the servo is engaged.
+    pub fn engaged(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getEngaged(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Engages or disengages the servo. The servo will not move until it is engaged.
+    pub fn set_engaged(&self, engaged: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setEngaged(self.chan, c_int::from(engaged))
+        })
+    }
+
+    /// Determines whether the servo is moving.
+    pub fn is_moving(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getIsMoving(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Gets the current position of the servo.
+    pub fn position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the position that corresponds to the minimum pulse width.
+    pub fn min_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMinPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the position that corresponds to the minimum pulse width.
+    pub fn set_min_position(&self, min_position: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_setMinPosition(self.chan, min_position) })
+    }
+
+    /// Gets the position that corresponds to the maximum pulse width.
+    pub fn max_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMaxPosition(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the position that corresponds to the maximum pulse width.
+    pub fn set_max_position(&self, max_position: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_setMaxPosition(self.chan, max_position) })
+    }
+
+    /// Gets the minimum pulse width, in microseconds.
+    pub fn min_pulse_width(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMinPulseWidth(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum pulse width, in microseconds.
+    pub fn set_min_pulse_width(&self, min_pulse_width: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setMinPulseWidth(self.chan, min_pulse_width)
+        })
+    }
+
+    /// Gets the maximum pulse width, in microseconds.
+    pub fn max_pulse_width(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMaxPulseWidth(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the maximum pulse width, in microseconds.
+    pub fn set_max_pulse_width(&self, max_pulse_width: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setMaxPulseWidth(self.chan, max_pulse_width)
+        })
+    }
+
+    /// Gets the lowest pulse width the controller can output, in microseconds.
+    pub fn min_pulse_width_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMinPulseWidthLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the highest pulse width the controller can output, in microseconds.
+    pub fn max_pulse_width_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMaxPulseWidthLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Determines whether speed ramping is enabled.
+    pub fn speed_ramping_state(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getSpeedRampingState(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables speed ramping. When disabled, the servo moves
+    /// to the target position as fast as it can.
+    pub fn set_speed_ramping_state(&self, on: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setSpeedRampingState(self.chan, c_int::from(on))
+        })
+    }
+
+    /// Gets the target position.
+    pub fn target_position(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getTargetPosition(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the position the servo should move to.
+    pub fn set_target_position(&self, target_position: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setTargetPosition(self.chan, target_position)
+        })
+    }
+
+    /// Gets the torque.
+    pub fn torque(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getTorque(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the torque.
+    pub fn set_torque(&self, torque: f64) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_setTorque(self.chan, torque) })
+    }
+
+    /// Gets the minimum torque.
+    pub fn min_torque(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMinTorque(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum torque.
+    pub fn max_torque(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getMaxTorque(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the current velocity of the servo.
+    pub fn velocity(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getVelocity(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the velocity limit.
+    pub fn velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getVelocityLimit(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Sets the velocity limit.
+    pub fn set_velocity_limit(&self, velocity_limit: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_setVelocityLimit(self.chan, velocity_limit)
+        })
+    }
+
+    /// Gets the minimum velocity limit.
+    pub fn min_velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMinVelocityLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum velocity limit.
+    pub fn max_velocity_limit(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRCServo_getMaxVelocityLimit(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the supply voltage for the servo.
+    pub fn voltage(&self) -> Result<RcServoVoltage> {
+        let mut value: ffi::PhidgetRCServo_Voltage = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_getVoltage(self.chan, &mut value) })?;
+        RcServoVoltage::try_from(value)
+    }
+
+    /// Sets the supply voltage for the servo.
+    pub fn set_voltage(&self, voltage: RcServoVoltage) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetRCServo_setVoltage(self.chan, voltage as c_uint) })
+    }
+
+    // Low-level, unsafe, callback for position change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_position_change(chan: RcServoHandle, ctx: *mut c_void, position: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<PositionChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive position change callbacks.
+    pub fn set_on_position_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&RcServo, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetRCServo_setOnPositionChangeHandler(
+                self.chan,
+                Some(Self::on_position_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for velocity change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_velocity_change(chan: RcServoHandle, ctx: *mut c_void, velocity: f64) {
+        if !ctx.is_null() {
+            let cb: &mut Box<VelocityChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, velocity);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive velocity change callbacks.
+    pub fn set_on_velocity_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&RcServo, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<VelocityChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetRCServo_setOnVelocityChangeHandler(
+                self.chan,
+                Some(Self::on_velocity_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<VelocityChangeCallback>(&mut self.velocity_change_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for target position reached events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_target_position_reached(
+        chan: RcServoHandle,
+        ctx: *mut c_void,
+        position: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<TargetPositionReachedCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, position);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive target position reached callbacks.
+    pub fn set_on_target_position_reached_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&RcServo, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<TargetPositionReachedCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetRCServo_setOnTargetPositionReachedHandler(
+                self.chan,
+                Some(Self::on_target_position_reached),
+                ctx,
+            )
+        };
+        self.retired_cbs.update::<TargetPositionReachedCallback>(
+            &mut self.target_position_reached_cb,
+            ctx,
+            rc,
+        )
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(move |_, position| tx.send(position))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetRCServo_setOnPositionChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of velocity change events.
+    ///
+    /// This replaces any velocity change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn velocity_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_velocity_change_handler(move |_, velocity| tx.send(velocity))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetRCServo_setOnVelocityChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<VelocityChangeCallback>(self.velocity_change_cb.take());
+        }))
+    }
+
+    /// Gets a stream of target position reached events.
+    ///
+    /// This replaces any target position reached handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn target_position_reached_events(
+        &mut self,
+    ) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_target_position_reached_handler(move |_, position| tx.send(position))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetRCServo_setOnTargetPositionReachedHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<TargetPositionReachedCallback>(self.target_position_reached_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any position change, velocity change, target position reached, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<RcServoChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, position| {
+                let _ = tx.send(Event::new(EventKind::Change(RcServoChange::Position(
+                    position,
+                ))));
+            }
+        })?;
+        self.set_on_velocity_change_handler({
+            let tx = tx.clone();
+            move |_, velocity| {
+                let _ = tx.send(Event::new(EventKind::Change(RcServoChange::Velocity(
+                    velocity,
+                ))));
+            }
+        })?;
+        self.set_on_target_position_reached_handler({
+            let tx = tx.clone();
+            move |_, position| {
+                let _ = tx.send(Event::new(EventKind::Change(
+                    RcServoChange::TargetPositionReached(position),
+                )));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for RcServo {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for RcServo {}
+
+impl Default for RcServo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RcServoHandle> for RcServo {
+    fn from(chan: RcServoHandle) -> Self {
+        Self {
+            chan,
+            position_change_cb: None,
+            velocity_change_cb: None,
+            target_position_reached_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for RcServo {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetRCServo_delete(&mut self.chan);
+            crate::drop_cb::<PositionChangeCallback>(self.position_change_cb.take());
+            crate::drop_cb::<VelocityChangeCallback>(self.velocity_change_cb.take());
+            crate::drop_cb::<TargetPositionReachedCallback>(self.target_position_reached_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,333 @@
+// phidget-rs/src/resistance_input.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{
+    self as ffi, PhidgetHandle, PhidgetResistanceInputHandle as ResistanceInputHandle,
+};
+use std::{
+    mem,
+    os::raw::{c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust resistance change callback.
+pub type ResistanceChangeCallback = dyn Fn(&ResistanceInput, f64) + Send + 'static;
+
+/// The wiring of an RTD (resistance temperature detector)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum RtdWireSetup {
+    /// 2-wire RTD
+    TwoWire = ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_2WIRE,
+    /// 3-wire RTD
+    ThreeWire = ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_3WIRE,
+    /// 4-wire RTD
+    FourWire = ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_4WIRE,
+}
+
+impl TryFrom<u32> for RtdWireSetup {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use RtdWireSetup::*;
+        match val {
+            ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_2WIRE => Ok(TwoWire),
+            ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_3WIRE => Ok(ThreeWire),
+            ffi::Phidget_RTDWireSetup_RTD_WIRE_SETUP_4WIRE => Ok(FourWire),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget resistance input
+pub struct ResistanceInput {
+    // Handle to the channel in the phidget22 library
+    chan: ResistanceInputHandle,
+    // Double-boxed ResistanceChangeCallback, if registered
+    resistance_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl ResistanceInput {
+    /// Create a new resistance input.
+    pub fn new() -> Self {
+        let mut chan: ResistanceInputHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetResistanceInput_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &ResistanceInputHandle {
+        &self.chan
+    }
+
+    /// Gets the measured resistance, in ohms.
+    pub fn resistance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getResistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum resistance that can be measured, in ohms.
+    pub fn min_resistance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getMinResistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum resistance that can be measured, in ohms.
+    pub fn max_resistance(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getMaxResistance(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the resistance change trigger.
+    pub fn resistance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getResistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in resistance, in ohms, that will fire
+    /// a resistance change event.
+    pub fn set_resistance_change_trigger(&self, resistance_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_setResistanceChangeTrigger(
+                self.chan,
+                resistance_change_trigger,
+            )
+        })
+    }
+
+    /// Gets the minimum resistance change trigger.
+    pub fn min_resistance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getMinResistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum resistance change trigger.
+    pub fn max_resistance_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getMaxResistanceChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the RTD wire setup.
+    pub fn rtd_wire_setup(&self) -> Result<RtdWireSetup> {
+        let mut value: ffi::Phidget_RTDWireSetup = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_getRTDWireSetup(self.chan, &mut value)
+        })?;
+        RtdWireSetup::try_from(value)
+    }
+
+    /// Sets the RTD wire setup to match the sensor.
+    pub fn set_rtd_wire_setup(&self, wire_setup: RtdWireSetup) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetResistanceInput_setRTDWireSetup(self.chan, wire_setup as c_uint)
+        })
+    }
+
+    // Low-level, unsafe, callback for resistance change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_resistance_change(
+        chan: ResistanceInputHandle,
+        ctx: *mut c_void,
+        resistance: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<ResistanceChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, resistance);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive resistance change callbacks.
+    pub fn set_on_resistance_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&ResistanceInput, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<ResistanceChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetResistanceInput_setOnResistanceChangeHandler(
+                self.chan,
+                Some(Self::on_resistance_change),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<ResistanceChangeCallback>(&mut self.resistance_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of resistance change events.
+    ///
+    /// This replaces any resistance change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn resistance_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_resistance_change_handler(move |_, resistance| tx.send(resistance))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetResistanceInput_setOnResistanceChangeHandler(
+                self.chan,
+                None,
+                ptr::null_mut(),
+            );
+            self.retired_cbs
+                .retire::<ResistanceChangeCallback>(self.resistance_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any resistance change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<f64>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_resistance_change_handler(crate::events::on_change(&tx))?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for ResistanceInput {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for ResistanceInput {}
+
+impl Default for ResistanceInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ResistanceInputHandle> for ResistanceInput {
+    fn from(chan: ResistanceInputHandle) -> Self {
+        Self {
+            chan,
+            resistance_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for ResistanceInput {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetResistanceInput_delete(&mut self.chan);
+            crate::drop_cb::<ResistanceChangeCallback>(self.resistance_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// phidget-rs/src/rfid.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetRFIDHandle as RfidHandle};
+use std::{
+    ffi::{CStr, CString},
+    mem,
+    os::raw::{c_char, c_int, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust tag callback.
+pub type TagCallback = dyn Fn(&Rfid, String, RfidProtocol) + Send + 'static;
+/// The function type for the safe Rust tag lost callback.
+pub type TagLostCallback = dyn Fn(&Rfid, String, RfidProtocol) + Send + 'static;
+
+/// A tag read by an RFID reader
+#[derive(Debug, Clone, PartialEq)]
+pub struct RfidTag {
+    /// The tag string
+    pub tag: String,
+    /// The protocol of the tag
+    pub protocol: RfidProtocol,
+}
+
+/// The value changes reported by an RFID reader through `Rfid::subscribe()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RfidChange {
+    /// A tag came into range of the reader
+    Tag(RfidTag),
+    /// A tag went out of range of the reader
+    TagLost(RfidTag),
+}
+
+/// The protocol used to communicate with an RFID tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RfidProtocol {
+    /// EM4100 (EM4102) 40-bit
+    Em4100,
+    /// ISO11785 FDX-B encoding (animal ID)
+    Iso11785FdxB,
+    /// PhidgetTAG protocol, with up to 24 ASCII characters
+    Phidgets,
+    /// A protocol that is not known to this crate, with its raw value
+    Unknown(u32),
+}
+
+impl From<u32> for RfidProtocol {
+    fn from(val: u32) -> Self {
+        use RfidProtocol::*;
+        match val {
+            ffi::PhidgetRFID_Protocol_PROTOCOL_EM4100 => Em4100,
+            ffi::PhidgetRFID_Protocol_PROTOCOL_ISO11785_FDX_B => Iso11785FdxB,
+            ffi::PhidgetRFID_Protocol_PROTOCOL_PHIDGETS => Phidgets,
+            _ => Unknown(val),
+        }
+    }
+}
+
+impl From<RfidProtocol> for u32 {
+    fn from(protocol: RfidProtocol) -> Self {
+        use RfidProtocol::*;
+        match protocol {
+            Em4100 => ffi::PhidgetRFID_Protocol_PROTOCOL_EM4100,
+            Iso11785FdxB => ffi::PhidgetRFID_Protocol_PROTOCOL_ISO11785_FDX_B,
+            Phidgets => ffi::PhidgetRFID_Protocol_PROTOCOL_PHIDGETS,
+            Unknown(val) => val,
+        }
+    }
+}
+
+// The size of the buffer used to read a tag string
+const TAG_BUF_LEN: usize = 64;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget RFID reader
+pub struct Rfid {
+    // Handle to the channel in the phidget22 library
+    chan: RfidHandle,
+    // Double-boxed TagCallback, if registered
+    tag_cb: Option<*mut c_void>,
+    // Double-boxed TagLostCallback, if registered
+    tag_lost_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Rfid {
+    /// Create a new RFID reader.
+    pub fn new() -> Self {
+        let mut chan: RfidHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetRFID_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &RfidHandle {
+        &self.chan
+    }
+
+    /// Determines whether the antenna is enabled.
+    pub fn antenna_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetRFID_getAntennaEnabled(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the antenna. Tags can only be read or written
+    /// while the antenna is enabled.
+    pub fn set_antenna_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRFID_setAntennaEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    /// Determines whether a tag is currently in range.
+    pub fn tag_present(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetRFID_getTagPresent(self.chan, &mut value) })?;
+        Ok(value != 0)
+    }
+
+    /// Gets the most recent tag that was read, even if it is no longer
+    /// in range.
+    pub fn last_tag(&self) -> Result<RfidTag> {
+        let mut buf = [0 as c_char; TAG_BUF_LEN];
+        let mut protocol: ffi::PhidgetRFID_Protocol = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRFID_getLastTag(self.chan, buf.as_mut_ptr(), buf.len(), &mut protocol)
+        })?;
+        let tag = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok(RfidTag {
+            tag: tag.to_string_lossy().into(),
+            protocol: RfidProtocol::from(protocol),
+        })
+    }
+
+    /// Writes a tag string to a writable tag in range of the reader,
+    /// optionally locking it so that it can not be written again.
+    pub fn write(&self, tag: &str, protocol: RfidProtocol, lock: bool) -> Result<()> {
+        let tag = CString::new(tag).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetRFID_write(
+                self.chan,
+                tag.as_ptr(),
+                u32::from(protocol),
+                c_int::from(lock),
+            )
+        })
+    }
+
+    // Low-level, unsafe, callback for tag events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_tag(
+        chan: RfidHandle,
+        ctx: *mut c_void,
+        tag: *const c_char,
+        protocol: ffi::PhidgetRFID_Protocol,
+    ) {
+        if !ctx.is_null() {
+            let tag = if tag.is_null() {
+                String::new()
+            }
+            else {
+                CStr::from_ptr(tag).to_string_lossy().into()
+            };
+            let cb: &mut Box<TagCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, tag, RfidProtocol::from(protocol));
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive tag callbacks.
+    pub fn set_on_tag_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Rfid, String, RfidProtocol) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<TagCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe { ffi::PhidgetRFID_setOnTagHandler(self.chan, Some(Self::on_tag), ctx) };
+        self.retired_cbs
+            .update::<TagCallback>(&mut self.tag_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for tag lost events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_tag_lost(
+        chan: RfidHandle,
+        ctx: *mut c_void,
+        tag: *const c_char,
+        protocol: ffi::PhidgetRFID_Protocol,
+    ) {
+        if !ctx.is_null() {
+            let tag = if tag.is_null() {
+                String::new()
+            }
+            else {
+                CStr::from_ptr(tag).to_string_lossy().into()
+            };
+            let cb: &mut Box<TagLostCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, tag, RfidProtocol::from(protocol));
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive tag lost callbacks.
+    pub fn set_on_tag_lost_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Rfid, String, RfidProtocol) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<TagLostCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetRFID_setOnTagLostHandler(self.chan, Some(Self::on_tag_lost), ctx)
+        };
+        self.retired_cbs
+            .update::<TagLostCallback>(&mut self.tag_lost_cb, ctx, rc)
+    }
+
+    /// Gets a stream of tag events.
+    ///
+    /// This replaces any tag handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn tag_events(&mut self) -> Result<crate::stream::EventStream<'_, RfidTag>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_tag_handler(move |_, tag, protocol| tx.send(RfidTag { tag, protocol }))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetRFID_setOnTagHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs.retire::<TagCallback>(self.tag_cb.take());
+        }))
+    }
+
+    /// Gets a stream of tag lost events.
+    ///
+    /// This replaces any tag lost handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn tag_lost_events(&mut self) -> Result<crate::stream::EventStream<'_, RfidTag>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_tag_lost_handler(move |_, tag, protocol| tx.send(RfidTag { tag, protocol }))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetRFID_setOnTagLostHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<TagLostCallback>(self.tag_lost_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any tag, tag lost, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<RfidChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_tag_handler({
+            let tx = tx.clone();
+            move |_, tag, protocol| {
+                let _ = tx.send(Event::new(EventKind::Change(RfidChange::Tag(RfidTag {
+                    tag,
+                    protocol,
+                }))));
+            }
+        })?;
+        self.set_on_tag_lost_handler({
+            let tx = tx.clone();
+            move |_, tag, protocol| {
+                let _ = tx.send(Event::new(EventKind::Change(RfidChange::TagLost(
+                    RfidTag { tag, protocol },
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Rfid {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Rfid {}
+
+impl Default for Rfid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RfidHandle> for Rfid {
+    fn from(chan: RfidHandle) -> Self {
+        Self {
+            chan,
+            tag_cb: None,
+            tag_lost_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Rfid {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetRFID_delete(&mut self.chan);
+            crate::drop_cb::<TagCallback>(self.tag_cb.take());
+            crate::drop_cb::<TagLostCallback>(self.tag_lost_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_round_trip() {
+        use RfidProtocol::*;
+        for protocol in [Em4100, Iso11785FdxB, Phidgets] {
+            assert_eq!(RfidProtocol::from(u32::from(protocol)), protocol);
+        }
+        assert_eq!(
+            RfidProtocol::from(ffi::PhidgetRFID_Protocol_PROTOCOL_EM4100),
+            Em4100
+        );
+        assert_eq!(RfidProtocol::from(0), Unknown(0));
+        assert_eq!(RfidProtocol::from(99), Unknown(99));
+        assert_eq!(u32::from(Unknown(99)), 99);
+    }
+}
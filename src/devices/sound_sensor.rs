@@ -0,0 +1,378 @@
+// phidget-rs/src/sound_sensor.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetSoundSensorHandle as SoundSensorHandle};
+use std::{
+    mem,
+    os::raw::{c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust SPL change callback.
+pub type SplChangeCallback =
+    dyn Fn(&SoundSensor, f64, f64, f64, [f64; OCTAVE_COUNT]) + Send + 'static;
+
+/// A sound pressure level (SPL) measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplChange {
+    /// The sound pressure level, in dB
+    pub db: f64,
+    /// The A-weighted sound pressure level, in dBA
+    pub dba: f64,
+    /// The C-weighted sound pressure level, in dBC
+    pub dbc: f64,
+    /// The sound pressure levels, in dB, of the octave bands
+    pub octaves: [f64; OCTAVE_COUNT],
+}
+
+/// The measurement range of the sound pressure level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum SplRange {
+    /// Range up to 102 dB
+    Db102 = ffi::PhidgetSoundSensor_SPLRange_SPL_RANGE_102dB,
+    /// Range up to 82 dB
+    Db82 = ffi::PhidgetSoundSensor_SPLRange_SPL_RANGE_82dB,
+}
+
+impl TryFrom<u32> for SplRange {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use SplRange::*;
+        match val {
+            ffi::PhidgetSoundSensor_SPLRange_SPL_RANGE_102dB => Ok(Db102),
+            ffi::PhidgetSoundSensor_SPLRange_SPL_RANGE_82dB => Ok(Db82),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// The number of octave bands measured by the sound sensor.
+pub const OCTAVE_COUNT: usize = 10;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget sound sensor
+pub struct SoundSensor {
+    // Handle to the channel in the phidget22 library
+    chan: SoundSensorHandle,
+    // Double-boxed SplChangeCallback, if registered
+    spl_change_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl SoundSensor {
+    /// Create a new sound sensor.
+    pub fn new() -> Self {
+        let mut chan: SoundSensorHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetSoundSensor_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &SoundSensorHandle {
+        &self.chan
+    }
+
+    /// Gets the measured sound pressure level, in dB.
+    pub fn db(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getdB(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum sound pressure level that can be measured, in dB.
+    pub fn max_db(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getMaxdB(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the A-weighted sound pressure level, in dBA.
+    pub fn dba(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getdBA(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the C-weighted sound pressure level, in dBC.
+    pub fn dbc(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getdBC(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum sound pressure level that can be measured, in dB.
+    pub fn noise_floor(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_getNoiseFloor(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the sound pressure levels, in dB, of the octave bands.
+    ///
+    /// The bands are centered at 31.5, 63, 125, 250, 500, 1k, 2k, 4k,
+    /// 8k, and 16k Hz.
+    pub fn octaves(&self) -> Result<[f64; OCTAVE_COUNT]> {
+        let mut value = [0.0; OCTAVE_COUNT];
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getOctaves(self.chan, &mut value) })?;
+        Ok(value)
+    }
+
+    /// Gets the SPL change trigger.
+    pub fn spl_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_getSPLChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the minimum change in sound pressure level, in dB, that will
+    /// fire an SPL change event.
+    pub fn set_spl_change_trigger(&self, spl_change_trigger: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_setSPLChangeTrigger(self.chan, spl_change_trigger)
+        })
+    }
+
+    /// Gets the minimum SPL change trigger.
+    pub fn min_spl_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_getMinSPLChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum SPL change trigger.
+    pub fn max_spl_change_trigger(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_getMaxSPLChangeTrigger(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the measurement range of the sensor.
+    pub fn spl_range(&self) -> Result<SplRange> {
+        let mut value: ffi::PhidgetSoundSensor_SPLRange = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetSoundSensor_getSPLRange(self.chan, &mut value) })?;
+        SplRange::try_from(value)
+    }
+
+    /// Sets the measurement range of the sensor.
+    pub fn set_spl_range(&self, range: SplRange) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSoundSensor_setSPLRange(self.chan, range as c_uint)
+        })
+    }
+
+    // Low-level, unsafe, callback for SPL change events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_spl_change(
+        chan: SoundSensorHandle,
+        ctx: *mut c_void,
+        db: f64,
+        dba: f64,
+        dbc: f64,
+        octaves: *const f64,
+    ) {
+        if !ctx.is_null() {
+            let octaves = if octaves.is_null() {
+                [0.0; OCTAVE_COUNT]
+            }
+            else {
+                *(octaves as *const [f64; OCTAVE_COUNT])
+            };
+            let cb: &mut Box<SplChangeCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(&ch, db, dba, dbc, octaves);
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive SPL change callbacks.
+    pub fn set_on_spl_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&SoundSensor, f64, f64, f64, [f64; OCTAVE_COUNT]) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<SplChangeCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetSoundSensor_setOnSPLChangeHandler(self.chan, Some(Self::on_spl_change), ctx)
+        };
+        self.retired_cbs
+            .update::<SplChangeCallback>(&mut self.spl_change_cb, ctx, rc)
+    }
+
+    /// Gets a stream of SPL change events.
+    ///
+    /// This replaces any SPL change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn spl_changes(&mut self) -> Result<crate::stream::EventStream<'_, SplChange>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_spl_change_handler(move |_, db, dba, dbc, octaves| {
+            tx.send(SplChange {
+                db,
+                dba,
+                dbc,
+                octaves,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetSoundSensor_setOnSPLChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<SplChangeCallback>(self.spl_change_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any SPL change, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<SplChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_spl_change_handler({
+            let tx = tx.clone();
+            move |_, db, dba, dbc, octaves| {
+                let _ = tx.send(Event::new(EventKind::Change(SplChange {
+                    db,
+                    dba,
+                    dbc,
+                    octaves,
+                })));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for SoundSensor {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for SoundSensor {}
+
+impl Default for SoundSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<SoundSensorHandle> for SoundSensor {
+    fn from(chan: SoundSensorHandle) -> Self {
+        Self {
+            chan,
+            spl_change_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for SoundSensor {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetSoundSensor_delete(&mut self.chan);
+            crate::drop_cb::<SplChangeCallback>(self.spl_change_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
@@ -0,0 +1,673 @@
+// phidget-rs/src/spatial.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetSpatialHandle as SpatialHandle};
+use std::{
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+/// The function type for the safe Rust spatial data callback.
+pub type SpatialDataCallback = dyn Fn(&Spatial, [f64; 3], [f64; 3], [f64; 3], f64) + Send + 'static;
+/// The function type for the safe Rust algorithm data callback.
+pub type AlgorithmDataCallback = dyn Fn(&Spatial, SpatialQuaternion, f64) + Send + 'static;
+
+/// A set of readings from the sensors of a spatial device
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialData {
+    /// The acceleration for each axis, in g
+    pub acceleration: [f64; 3],
+    /// The angular rate for each axis, in degrees/sec
+    pub angular_rate: [f64; 3],
+    /// The magnetic field for each axis, in gauss
+    pub magnetic_field: [f64; 3],
+    /// The timestamp of the data, in milliseconds
+    pub timestamp: f64,
+}
+
+/// The output of the sensor fusion algorithm of a spatial device
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlgorithmData {
+    /// The orientation computed by the algorithm
+    pub quaternion: SpatialQuaternion,
+    /// The timestamp of the data, in milliseconds
+    pub timestamp: f64,
+}
+
+/// The value changes reported by a spatial device through `Spatial::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpatialChange {
+    /// Spatial data
+    Spatial(SpatialData),
+    /// Algorithm data
+    Algorithm(AlgorithmData),
+}
+
+/// The sensor fusion algorithm used to compute the orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum SpatialAlgorithm {
+    /// No algorithm, the orientation is not computed
+    None = ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_NONE,
+    /// Attitude and heading reference system, using the magnetometer
+    Ahrs = ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_AHRS,
+    /// Inertial measurement unit, without the magnetometer
+    Imu = ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_IMU,
+}
+
+impl TryFrom<u32> for SpatialAlgorithm {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use SpatialAlgorithm::*;
+        match val {
+            ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_NONE => Ok(None),
+            ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_AHRS => Ok(Ahrs),
+            ffi::Phidget_SpatialAlgorithm_SPATIAL_ALGORITHM_IMU => Ok(Imu),
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+/// An orientation, as a quaternion
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpatialQuaternion {
+    /// The x component
+    pub x: f64,
+    /// The y component
+    pub y: f64,
+    /// The z component
+    pub z: f64,
+    /// The w (scalar) component
+    pub w: f64,
+}
+
+impl From<[f64; 4]> for SpatialQuaternion {
+    fn from(q: [f64; 4]) -> Self {
+        Self {
+            x: q[0],
+            y: q[1],
+            z: q[2],
+            w: q[3],
+        }
+    }
+}
+
+impl From<ffi::PhidgetSpatial_SpatialQuaternion> for SpatialQuaternion {
+    fn from(q: ffi::PhidgetSpatial_SpatialQuaternion) -> Self {
+        Self {
+            x: q.x,
+            y: q.y,
+            z: q.z,
+            w: q.w,
+        }
+    }
+}
+
+/// An orientation, as Euler angles, in degrees
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpatialEulerAngles {
+    /// The pitch angle
+    pub pitch: f64,
+    /// The roll angle
+    pub roll: f64,
+    /// The heading angle
+    pub heading: f64,
+}
+
+impl From<ffi::PhidgetSpatial_SpatialEulerAngles> for SpatialEulerAngles {
+    fn from(a: ffi::PhidgetSpatial_SpatialEulerAngles) -> Self {
+        Self {
+            pitch: a.pitch,
+            roll: a.roll,
+            heading: a.heading,
+        }
+    }
+}
+
+/// The tuning parameters for the AHRS algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AhrsParameters {
+    /// The maximum angular velocity, in degrees/sec, at which the device
+    /// is considered to be at rest
+    pub angular_velocity_threshold: f64,
+    /// The maximum change in angular velocity, in degrees/sec, at which
+    /// the device is considered to be at rest
+    pub angular_velocity_delta_threshold: f64,
+    /// The maximum acceleration, in g, at which the device is considered
+    /// to be at rest
+    pub acceleration_threshold: f64,
+    /// The time, in seconds, to converge to the magnetometer reading
+    pub mag_time: f64,
+    /// The time, in seconds, to converge to the accelerometer reading
+    pub accel_time: f64,
+    /// The time, in seconds, to converge the gyro bias at rest
+    pub bias_time: f64,
+}
+
+/// The calibration parameters for a magnetometer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MagnetometerCorrection {
+    /// The ambient magnetic field strength, in gauss
+    pub magnetic_field: f64,
+    /// The offsets for the x, y, and z axes
+    pub offset: [f64; 3],
+    /// The gains for the x, y, and z axes
+    pub gain: [f64; 3],
+    /// The non-orthogonality correction factors
+    pub t: [f64; 6],
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Phidget spatial (IMU) device
+pub struct Spatial {
+    // Handle to the channel in the phidget22 library
+    chan: SpatialHandle,
+    // Double-boxed SpatialDataCallback, if registered
+    spatial_data_cb: Option<*mut c_void>,
+    // Double-boxed AlgorithmDataCallback, if registered
+    algorithm_data_cb: Option<*mut c_void>,
+    // Double-boxed attach callback, if registered
+    attach_cb: Option<*mut c_void>,
+    // Double-boxed detach callback, if registered
+    detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
+}
+
+impl Spatial {
+    /// Create a new spatial (IMU) device.
+    pub fn new() -> Self {
+        let mut chan: SpatialHandle = ptr::null_mut();
+        unsafe {
+            ffi::PhidgetSpatial_create(&mut chan);
+        }
+        Self::from(chan)
+    }
+
+    /// Get a reference to the underlying channel handle
+    pub fn as_channel(&self) -> &SpatialHandle {
+        &self.chan
+    }
+
+    /// Gets the sensor fusion algorithm.
+    pub fn algorithm(&self) -> Result<SpatialAlgorithm> {
+        let mut value: ffi::Phidget_SpatialAlgorithm = 0;
+        ReturnCode::result(unsafe { ffi::PhidgetSpatial_getAlgorithm(self.chan, &mut value) })?;
+        SpatialAlgorithm::try_from(value)
+    }
+
+    /// Sets the sensor fusion algorithm used to compute the orientation.
+    pub fn set_algorithm(&self, algorithm: SpatialAlgorithm) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_setAlgorithm(self.chan, algorithm as c_uint)
+        })
+    }
+
+    /// Gets the weight given to the magnetometer by the AHRS algorithm.
+    pub fn algorithm_magnetometer_gain(&self) -> Result<f64> {
+        let mut value = 0.0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getAlgorithmMagnetometerGain(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the weight given to the magnetometer by the AHRS algorithm.
+    pub fn set_algorithm_magnetometer_gain(&self, gain: f64) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_setAlgorithmMagnetometerGain(self.chan, gain)
+        })
+    }
+
+    /// Sets the tuning parameters for the AHRS algorithm.
+    pub fn set_ahrs_parameters(&self, params: &AhrsParameters) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_setAHRSParameters(
+                self.chan,
+                params.angular_velocity_threshold,
+                params.angular_velocity_delta_threshold,
+                params.acceleration_threshold,
+                params.mag_time,
+                params.accel_time,
+                params.bias_time,
+            )
+        })
+    }
+
+    /// Sets the magnetometer calibration parameters.
+    ///
+    /// These take effect immediately, but are lost when the device is
+    /// powered off unless they are saved with
+    /// [`save_magnetometer_correction_parameters()`](Self::save_magnetometer_correction_parameters).
+    pub fn set_magnetometer_correction_parameters(
+        &self,
+        params: &MagnetometerCorrection,
+    ) -> Result<()> {
+        let MagnetometerCorrection {
+            magnetic_field,
+            offset,
+            gain,
+            t,
+        } = *params;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_setMagnetometerCorrectionParameters(
+                self.chan,
+                magnetic_field,
+                offset[0],
+                offset[1],
+                offset[2],
+                gain[0],
+                gain[1],
+                gain[2],
+                t[0],
+                t[1],
+                t[2],
+                t[3],
+                t[4],
+                t[5],
+            )
+        })
+    }
+
+    /// Resets the magnetometer calibration parameters to their defaults.
+    pub fn reset_magnetometer_correction_parameters(&self) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_resetMagnetometerCorrectionParameters(self.chan)
+        })
+    }
+
+    /// Saves the magnetometer calibration parameters to the device flash.
+    pub fn save_magnetometer_correction_parameters(&self) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_saveMagnetometerCorrectionParameters(self.chan)
+        })
+    }
+
+    /// Zeroes the orientation computed by the sensor fusion algorithm.
+    pub fn zero_algorithm(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetSpatial_zeroAlgorithm(self.chan) })
+    }
+
+    /// Re-zeroes the gyroscope. The device must be held still while
+    /// this is in progress.
+    pub fn zero_gyro(&self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetSpatial_zeroGyro(self.chan) })
+    }
+
+    /// Gets the minimum acceleration for each axis, in g.
+    pub fn min_acceleration(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMinAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum acceleration for each axis, in g.
+    pub fn max_acceleration(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMaxAcceleration(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum angular rate for each axis, in degrees/sec.
+    pub fn min_angular_rate(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMinAngularRate(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum angular rate for each axis, in degrees/sec.
+    pub fn max_angular_rate(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMaxAngularRate(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the minimum magnetic field for each axis, in gauss.
+    pub fn min_magnetic_field(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMinMagneticField(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the maximum magnetic field for each axis, in gauss.
+    pub fn max_magnetic_field(&self) -> Result<[f64; 3]> {
+        let mut value = [0.0; 3];
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getMaxMagneticField(self.chan, &mut value)
+        })?;
+        Ok(value)
+    }
+
+    /// Gets the orientation computed by the sensor fusion algorithm,
+    /// as Euler angles.
+    pub fn euler_angles(&self) -> Result<SpatialEulerAngles> {
+        let mut value = ffi::PhidgetSpatial_SpatialEulerAngles {
+            pitch: 0.0,
+            roll: 0.0,
+            heading: 0.0,
+        };
+        ReturnCode::result(unsafe { ffi::PhidgetSpatial_getEulerAngles(self.chan, &mut value) })?;
+        Ok(value.into())
+    }
+
+    /// Gets the orientation computed by the sensor fusion algorithm,
+    /// as a quaternion.
+    pub fn quaternion(&self) -> Result<SpatialQuaternion> {
+        let mut value = ffi::PhidgetSpatial_SpatialQuaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        ReturnCode::result(unsafe { ffi::PhidgetSpatial_getQuaternion(self.chan, &mut value) })?;
+        Ok(value.into())
+    }
+
+    /// Determines whether the internal heater is enabled.
+    pub fn heating_enabled(&self) -> Result<bool> {
+        let mut value = 0;
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_getHeatingEnabled(self.chan, &mut value)
+        })?;
+        Ok(value != 0)
+    }
+
+    /// Enables or disables the internal heater, which keeps the sensors
+    /// at a stable temperature.
+    pub fn set_heating_enabled(&self, enabled: bool) -> Result<()> {
+        ReturnCode::result(unsafe {
+            ffi::PhidgetSpatial_setHeatingEnabled(self.chan, c_int::from(enabled))
+        })
+    }
+
+    // Low-level, unsafe, callback for spatial data events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_spatial_data(
+        chan: SpatialHandle,
+        ctx: *mut c_void,
+        acceleration: *const f64,
+        angular_rate: *const f64,
+        magnetic_field: *const f64,
+        timestamp: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<SpatialDataCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(
+                &ch,
+                *(acceleration as *const [f64; 3]),
+                *(angular_rate as *const [f64; 3]),
+                *(magnetic_field as *const [f64; 3]),
+                timestamp,
+            );
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive spatial data callbacks.
+    pub fn set_on_spatial_data_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Spatial, [f64; 3], [f64; 3], [f64; 3], f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<SpatialDataCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetSpatial_setOnSpatialDataHandler(self.chan, Some(Self::on_spatial_data), ctx)
+        };
+        self.retired_cbs
+            .update::<SpatialDataCallback>(&mut self.spatial_data_cb, ctx, rc)
+    }
+
+    // Low-level, unsafe, callback for algorithm data events.
+    // The context is a double-boxed pointer to the safe Rust callback.
+    unsafe extern "C" fn on_algorithm_data(
+        chan: SpatialHandle,
+        ctx: *mut c_void,
+        quaternion: *const f64,
+        timestamp: f64,
+    ) {
+        if !ctx.is_null() {
+            let cb: &mut Box<AlgorithmDataCallback> = &mut *(ctx as *mut _);
+            let ch = Self::from(chan);
+            cb(
+                &ch,
+                SpatialQuaternion::from(*(quaternion as *const [f64; 4])),
+                timestamp,
+            );
+            mem::forget(ch);
+        }
+    }
+
+    /// Sets a handler to receive algorithm data callbacks.
+    pub fn set_on_algorithm_data_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&Spatial, SpatialQuaternion, f64) + Send + 'static,
+    {
+        // 1st box is fat ptr, 2nd is regular pointer.
+        let cb: Box<Box<AlgorithmDataCallback>> = Box::new(Box::new(cb));
+        let ctx = Box::into_raw(cb) as *mut c_void;
+
+        let rc = unsafe {
+            ffi::PhidgetSpatial_setOnAlgorithmDataHandler(
+                self.chan,
+                Some(Self::on_algorithm_data),
+                ctx,
+            )
+        };
+        self.retired_cbs
+            .update::<AlgorithmDataCallback>(&mut self.algorithm_data_cb, ctx, rc)
+    }
+
+    /// Gets a stream of spatial data events.
+    ///
+    /// This replaces any spatial data handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn spatial_data(&mut self) -> Result<crate::stream::EventStream<'_, SpatialData>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_spatial_data_handler(
+            move |_, acceleration, angular_rate, magnetic_field, timestamp| {
+                tx.send(SpatialData {
+                    acceleration,
+                    angular_rate,
+                    magnetic_field,
+                    timestamp,
+                })
+            },
+        )?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetSpatial_setOnSpatialDataHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<SpatialDataCallback>(self.spatial_data_cb.take());
+        }))
+    }
+
+    /// Gets a stream of algorithm data events.
+    ///
+    /// This replaces any algorithm data handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn algorithm_data(&mut self) -> Result<crate::stream::EventStream<'_, AlgorithmData>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_algorithm_data_handler(move |_, quaternion, timestamp| {
+            tx.send(AlgorithmData {
+                quaternion,
+                timestamp,
+            })
+        })?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetSpatial_setOnAlgorithmDataHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<AlgorithmDataCallback>(self.algorithm_data_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any spatial data, algorithm data, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<SpatialChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_spatial_data_handler({
+            let tx = tx.clone();
+            move |_, acceleration, angular_rate, magnetic_field, timestamp| {
+                let _ = tx.send(Event::new(EventKind::Change(SpatialChange::Spatial(
+                    SpatialData {
+                        acceleration,
+                        angular_rate,
+                        magnetic_field,
+                        timestamp,
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_algorithm_data_handler({
+            let tx = tx.clone();
+            move |_, quaternion, timestamp| {
+                let _ = tx.send(Event::new(EventKind::Change(SpatialChange::Algorithm(
+                    AlgorithmData {
+                        quaternion,
+                        timestamp,
+                    },
+                ))));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
+    }
+
+    /// Sets a handler to receive attach callbacks
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive detach callbacks
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
+        Ok(())
+    }
+}
+
+impl Phidget for Spatial {
+    fn as_handle(&mut self) -> PhidgetHandle {
+        self.chan as PhidgetHandle
+    }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
+}
+
+unsafe impl Send for Spatial {}
+
+impl Default for Spatial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<SpatialHandle> for Spatial {
+    fn from(chan: SpatialHandle) -> Self {
+        Self {
+            chan,
+            spatial_data_cb: None,
+            algorithm_data_cb: None,
+            attach_cb: None,
+            detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
+        }
+    }
+}
+
+impl Drop for Spatial {
+    fn drop(&mut self) {
+        if let Ok(true) = self.is_open() {
+            let _ = self.close();
+        }
+        unsafe {
+            ffi::PhidgetSpatial_delete(&mut self.chan);
+            crate::drop_cb::<SpatialDataCallback>(self.spatial_data_cb.take());
+            crate::drop_cb::<AlgorithmDataCallback>(self.algorithm_data_cb.take());
+            crate::drop_cb::<AttachCallback>(self.attach_cb.take());
+            crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
+        }
+    }
+}
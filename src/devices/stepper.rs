@@ -12,7 +12,11 @@
 // to those terms.
 //
 
-use crate::{AttachCallback, DetachCallback, Error, GenericPhidget, Phidget, Result, ReturnCode};
+use crate::{
+    events::{Event, EventKind, Receiver},
+    AttachCallback, DetachCallback, Error, ErrorCallback, ErrorEventCode, GenericPhidget, Phidget,
+    Property, PropertyChangeCallback, Result, ReturnCode,
+};
 use phidget_sys::{self as ffi, PhidgetHandle, PhidgetStepperHandle as StepperHandle};
 use std::{
     mem,
@@ -31,12 +35,22 @@ pub type StoppedCallback = dyn Fn(&Stepper) + Send + 'static;
 pub struct Stepper {
     // Handle to the sensor for the phidget22 library
     chan: StepperHandle,
-    // Double-boxed StepperCallback, if registered
-    cb: Option<*mut c_void>,
+    // Double-boxed PositionChangeCallback, if registered
+    position_cb: Option<*mut c_void>,
+    // Double-boxed VelocityChangeCallback, if registered
+    velocity_cb: Option<*mut c_void>,
+    // Double-boxed StoppedCallback, if registered
+    stopped_cb: Option<*mut c_void>,
     // Double-boxed attach callback, if registered
     attach_cb: Option<*mut c_void>,
     // Double-boxed detach callback, if registered
     detach_cb: Option<*mut c_void>,
+    // Double-boxed error callback, if registered
+    error_cb: Option<*mut c_void>,
+    // Double-boxed property change callback, if registered
+    property_cb: Option<*mut c_void>,
+    // Callbacks that were replaced, released when the channel is dropped
+    retired_cbs: crate::RetiredCallbacks,
 }
 
 /// ControlMode for stepper
@@ -62,6 +76,17 @@ impl TryFrom<u32> for ControlMode {
     }
 }
 
+/// The value changes reported by a stepper through `Stepper::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepperChange {
+    /// The position changed
+    Position(f64),
+    /// The velocity changed
+    Velocity(f64),
+    /// The motor stopped
+    Stopped,
+}
+
 impl Stepper {
     /// Create a new Stepper sensor.
     pub fn new() -> Self {
@@ -326,7 +351,7 @@ impl Stepper {
         ReturnCode::result(unsafe { ffi::PhidgetStepper_setTargetPosition(self.chan, stepper) })?;
         Ok(())
     }
-    /// [NOT IMPLEMENTED] Set target position async TODO
+    // /// [NOT IMPLEMENTED] Set target position async TODO
     // pub async fn set_target_position_async(&self, stepper: f64) -> Result<()> {
     //     _ = stepper;
     //     unimplemented!();
@@ -393,15 +418,16 @@ impl Stepper {
         // 1st box is fat ptr, 2nd is regular pointer.
         let cb: Box<Box<PositionChangeCallback>> = Box::new(Box::new(cb));
         let ctx = Box::into_raw(cb) as *mut c_void;
-        self.cb = Some(ctx);
 
-        ReturnCode::result(unsafe {
+        let rc = unsafe {
             ffi::PhidgetStepper_setOnPositionChangeHandler(
                 self.chan,
                 Some(Self::on_position_change),
                 ctx,
             )
-        })
+        };
+        self.retired_cbs
+            .update::<PositionChangeCallback>(&mut self.position_cb, ctx, rc)
     }
 
     // Low-level, unsafe, callback for stop events.
@@ -423,11 +449,12 @@ impl Stepper {
         // 1st box is fat ptr, 2nd is regular pointer.
         let cb: Box<Box<StoppedCallback>> = Box::new(Box::new(cb));
         let ctx = Box::into_raw(cb) as *mut c_void;
-        self.cb = Some(ctx);
 
-        ReturnCode::result(unsafe {
+        let rc = unsafe {
             ffi::PhidgetStepper_setOnStoppedHandler(self.chan, Some(Self::on_stopped), ctx)
-        })
+        };
+        self.retired_cbs
+            .update::<StoppedCallback>(&mut self.stopped_cb, ctx, rc)
     }
 
     // Low-level, unsafe, callback for velocity change events.
@@ -449,15 +476,100 @@ impl Stepper {
         // 1st box is fat ptr, 2nd is regular pointer.
         let cb: Box<Box<VelocityChangeCallback>> = Box::new(Box::new(cb));
         let ctx = Box::into_raw(cb) as *mut c_void;
-        self.cb = Some(ctx);
 
-        ReturnCode::result(unsafe {
+        let rc = unsafe {
             ffi::PhidgetStepper_setOnVelocityChangeHandler(
                 self.chan,
                 Some(Self::on_velocity_change),
                 ctx,
             )
-        })
+        };
+        self.retired_cbs
+            .update::<VelocityChangeCallback>(&mut self.velocity_cb, ctx, rc)
+    }
+
+    /// Gets a stream of position change events.
+    ///
+    /// This replaces any position change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn position_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_position_change_handler(move |_, pos| tx.send(pos))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetStepper_setOnPositionChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<PositionChangeCallback>(self.position_cb.take());
+        }))
+    }
+
+    /// Gets a stream of velocity change events.
+    ///
+    /// This replaces any velocity change handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn velocity_changes(&mut self) -> Result<crate::stream::EventStream<'_, f64>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_velocity_change_handler(move |_, vel| tx.send(vel))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetStepper_setOnVelocityChangeHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<VelocityChangeCallback>(self.velocity_cb.take());
+        }))
+    }
+
+    /// Gets a stream of stop events.
+    ///
+    /// This replaces any stop handler that was previously set.
+    /// The handler is removed when the stream is dropped.
+    /// If the stream falls behind, events are discarded as set by its
+    /// [`Overflow`](crate::stream::Overflow) policy; see
+    /// [`EventStream::dropped()`](crate::stream::EventStream::dropped).
+    #[cfg(feature = "async")]
+    pub fn stopped_events(&mut self) -> Result<crate::stream::EventStream<'_, ()>> {
+        let (tx, rx) = crate::stream::channel();
+        self.set_on_stopped_handler(move |_| tx.send(()))?;
+        Ok(crate::stream::EventStream::new(rx, move || unsafe {
+            ffi::PhidgetStepper_setOnStoppedHandler(self.chan, None, ptr::null_mut());
+            self.retired_cbs
+                .retire::<StoppedCallback>(self.stopped_cb.take());
+        }))
+    }
+
+    /// Subscribes to all the events from the channel.
+    ///
+    /// This replaces any position change, velocity change, stop, attach, detach, and error handlers
+    /// that were previously set, and returns a receiver for the events.
+    pub fn subscribe(&mut self) -> Result<Receiver<Event<StepperChange>>> {
+        let (tx, rx) = crate::events::channel();
+        self.set_on_position_change_handler({
+            let tx = tx.clone();
+            move |_, pos| {
+                let _ = tx.send(Event::new(EventKind::Change(StepperChange::Position(pos))));
+            }
+        })?;
+        self.set_on_velocity_change_handler({
+            let tx = tx.clone();
+            move |_, vel| {
+                let _ = tx.send(Event::new(EventKind::Change(StepperChange::Velocity(vel))));
+            }
+        })?;
+        self.set_on_stopped_handler({
+            let tx = tx.clone();
+            move |_| {
+                let _ = tx.send(Event::new(EventKind::Change(StepperChange::Stopped)));
+            }
+        })?;
+        self.set_on_attach_handler(crate::events::on_attach(&tx))?;
+        self.set_on_detach_handler(crate::events::on_detach(&tx))?;
+        self.set_on_error_handler(crate::events::on_error(&tx))?;
+        Ok(rx)
     }
 
     /// Sets a handler to receive attach callbacks
@@ -466,7 +578,8 @@ impl Stepper {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_attach_handler(self, cb)?;
-        self.attach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<AttachCallback>(self.attach_cb.replace(ctx));
         Ok(())
     }
 
@@ -476,7 +589,30 @@ impl Stepper {
         F: Fn(&GenericPhidget) + Send + 'static,
     {
         let ctx = crate::phidget::set_on_detach_handler(self, cb)?;
-        self.detach_cb = Some(ctx);
+        self.retired_cbs
+            .retire::<DetachCallback>(self.detach_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive error callbacks
+    pub fn set_on_error_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_error_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<ErrorCallback>(self.error_cb.replace(ctx));
+        Ok(())
+    }
+
+    /// Sets a handler to receive property change callbacks
+    pub fn set_on_property_change_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, Property) + Send + 'static,
+    {
+        let ctx = crate::phidget::set_on_property_change_handler(self, cb)?;
+        self.retired_cbs
+            .retire::<PropertyChangeCallback>(self.property_cb.replace(ctx));
         Ok(())
     }
 }
@@ -485,6 +621,10 @@ impl Phidget for Stepper {
     fn as_handle(&mut self) -> PhidgetHandle {
         self.chan as PhidgetHandle
     }
+
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        self.attach_cb
+    }
 }
 
 unsafe impl Send for Stepper {}
@@ -499,9 +639,14 @@ impl From<StepperHandle> for Stepper {
     fn from(chan: StepperHandle) -> Self {
         Self {
             chan,
-            cb: None,
+            position_cb: None,
+            velocity_cb: None,
+            stopped_cb: None,
             attach_cb: None,
             detach_cb: None,
+            error_cb: None,
+            property_cb: None,
+            retired_cbs: crate::RetiredCallbacks::default(),
         }
     }
 }
@@ -513,11 +658,13 @@ impl Drop for Stepper {
         }
         unsafe {
             ffi::PhidgetStepper_delete(&mut self.chan);
-            crate::drop_cb::<PositionChangeCallback>(self.cb.take());
-            crate::drop_cb::<VelocityChangeCallback>(self.cb.take());
-            crate::drop_cb::<StoppedCallback>(self.cb.take());
+            crate::drop_cb::<PositionChangeCallback>(self.position_cb.take());
+            crate::drop_cb::<VelocityChangeCallback>(self.velocity_cb.take());
+            crate::drop_cb::<StoppedCallback>(self.stopped_cb.take());
             crate::drop_cb::<AttachCallback>(self.attach_cb.take());
             crate::drop_cb::<DetachCallback>(self.detach_cb.take());
+            crate::drop_cb::<ErrorCallback>(self.error_cb.take());
+            crate::drop_cb::<PropertyChangeCallback>(self.property_cb.take());
         }
     }
 }
@@ -187,3 +187,100 @@ pub type Error = ReturnCode;
 
 /// The default result type for the phidget-rs library
 pub type Result<T> = std::result::Result<T, Error>;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Error event codes from the phidget22 library.
+/// These are the codes delivered asynchronously to the error event
+/// handler of a channel, such as when a measurement is out of range or
+/// a packet was lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum ErrorEventCode {
+    /// Client and server protocol versions don't match
+    BadVersion = ffi::Phidget_ErrorEventCode_EEPHIDGET_BADVERSION, // 1
+    /// The device is already opened by another client
+    Busy = ffi::Phidget_ErrorEventCode_EEPHIDGET_BUSY, // 2
+    /// A network error occurred
+    Network = ffi::Phidget_ErrorEventCode_EEPHIDGET_NETWORK, // 3
+    /// Error dispatching the event
+    Dispatch = ffi::Phidget_ErrorEventCode_EEPHIDGET_DISPATCH, // 4
+    /// A general failure occurred
+    Failure = ffi::Phidget_ErrorEventCode_EEPHIDGET_FAILURE, // 5
+    /// A previously reported error condition has been resolved
+    Ok = ffi::Phidget_ErrorEventCode_EEPHIDGET_OK, // 4096
+    /// The sampling rate is too high, and data was lost
+    Overrun = ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERRUN, // 4098
+    /// A packet was lost
+    PacketLost = ffi::Phidget_ErrorEventCode_EEPHIDGET_PACKETLOST, // 4099
+    /// A variable has wrapped around
+    Wrap = ffi::Phidget_ErrorEventCode_EEPHIDGET_WRAP, // 4100
+    /// The device is over temperature
+    OverTemp = ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERTEMP, // 4101
+    /// The device is drawing too much current
+    OverCurrent = ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERCURRENT, // 4102
+    /// The measurement is out of range
+    OutOfRange = ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGE, // 4103
+    /// The device has insufficient or bad power
+    BadPower = ffi::Phidget_ErrorEventCode_EEPHIDGET_BADPOWER, // 4104
+    /// The sensor has saturated
+    Saturation = ffi::Phidget_ErrorEventCode_EEPHIDGET_SATURATION, // 4105
+    /// The device is over voltage
+    OverVoltage = ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERVOLTAGE, // 4107
+    /// The failsafe timer has expired
+    Failsafe = ffi::Phidget_ErrorEventCode_EEPHIDGET_FAILSAFE, // 4108
+    /// A voltage error occurred
+    VoltageError = ffi::Phidget_ErrorEventCode_EEPHIDGET_VOLTAGEERROR, // 4109
+    /// The device is dumping excess energy
+    EnergyDump = ffi::Phidget_ErrorEventCode_EEPHIDGET_ENERGYDUMP, // 4110
+    /// The motor has stalled
+    MotorStall = ffi::Phidget_ErrorEventCode_EEPHIDGET_MOTORSTALL, // 4111
+    /// The device is in an invalid state
+    InvalidState = ffi::Phidget_ErrorEventCode_EEPHIDGET_INVALIDSTATE, // 4112
+    /// The connection to the sensor is bad
+    BadConnection = ffi::Phidget_ErrorEventCode_EEPHIDGET_BADCONNECTION, // 4113
+    /// The measurement is above the valid range
+    OutOfRangeHigh = ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGEHIGH, // 4114
+    /// The measurement is below the valid range
+    OutOfRangeLow = ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGELOW, // 4115
+    /// A fault was detected
+    Fault = ffi::Phidget_ErrorEventCode_EEPHIDGET_FAULT, // 4116
+    /// The external emergency stop was triggered
+    EStop = ffi::Phidget_ErrorEventCode_EEPHIDGET_ESTOP, // 4117
+}
+
+impl From<c_uint> for ErrorEventCode {
+    /// Converts an unsigned integer into an `ErrorEventCode`.
+    /// Note that instead of implementing `try_from`, any unknown integer
+    /// value is returned as an `ErrorEventCode::Failure`.
+    fn from(val: c_uint) -> Self {
+        use ErrorEventCode::*;
+        match val {
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_BADVERSION => BadVersion, // 1
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_BUSY => Busy,             // 2
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_NETWORK => Network,       // 3
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_DISPATCH => Dispatch,     // 4
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OK => Ok,                 // 4096
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERRUN => Overrun,       // 4098
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_PACKETLOST => PacketLost, // 4099
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_WRAP => Wrap,             // 4100
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERTEMP => OverTemp,     // 4101
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERCURRENT => OverCurrent, // 4102
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGE => OutOfRange, // 4103
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_BADPOWER => BadPower,     // 4104
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_SATURATION => Saturation, // 4105
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OVERVOLTAGE => OverVoltage, // 4107
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_FAILSAFE => Failsafe,     // 4108
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_VOLTAGEERROR => VoltageError, // 4109
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_ENERGYDUMP => EnergyDump, // 4110
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_MOTORSTALL => MotorStall, // 4111
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_INVALIDSTATE => InvalidState, // 4112
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_BADCONNECTION => BadConnection, // 4113
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGEHIGH => OutOfRangeHigh, // 4114
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_OUTOFRANGELOW => OutOfRangeLow, // 4115
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_FAULT => Fault,           // 4116
+            ffi::Phidget_ErrorEventCode_EEPHIDGET_ESTOP => EStop,           // 4117
+            _ => Failure,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// phidget-rs/src/events.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+//! Channel-based event delivery.
+//!
+//! Each device type has a `subscribe()` function that registers handlers
+//! for all of the channel's events, and returns a
+//! [`Receiver`](crossbeam_channel::Receiver) for them. The events are
+//! timestamped when they arrive from the phidget22 library and are queued
+//! in an unbounded channel, so the library's event thread is never
+//! blocked.
+//!
+//! The receivers come from the `crossbeam-channel` crate, so a single
+//! thread can wait on the events from many devices using its `select!`
+//! macro or `Select` type.
+//!
+
+use crate::{ErrorEventCode, GenericPhidget};
+use std::time::Instant;
+
+pub use crossbeam_channel::{Receiver, Sender};
+
+/// The kind of event that was received from a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind<T> {
+    /// The value of the channel changed.
+    /// The type of the value is specific to each device.
+    Change(T),
+    /// The channel was attached
+    Attach,
+    /// The channel was detached
+    Detach,
+    /// An error event occurred, with a code and description
+    Error(ErrorEventCode, String),
+}
+
+/// A timestamped event received from a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event<T> {
+    /// The time at which the event was received from the library
+    pub timestamp: Instant,
+    /// The event
+    pub kind: EventKind<T>,
+}
+
+impl<T> Event<T> {
+    /// Creates a new event, timestamped now.
+    pub fn new(kind: EventKind<T>) -> Self {
+        Self {
+            timestamp: Instant::now(),
+            kind,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Helpers for the device types to forward their callbacks to a channel.
+
+/// Creates an unbounded channel for events.
+pub(crate) fn channel<T>() -> (Sender<Event<T>>, Receiver<Event<T>>) {
+    crossbeam_channel::unbounded()
+}
+
+/// Creates a value change callback that forwards to the channel.
+pub(crate) fn on_change<D, T>(tx: &Sender<Event<T>>) -> impl Fn(&D, T) + Send + 'static
+where
+    T: Send + 'static,
+{
+    let tx = tx.clone();
+    move |_, val| {
+        let _ = tx.send(Event::new(EventKind::Change(val)));
+    }
+}
+
+/// Creates an attach callback that forwards to the channel.
+pub(crate) fn on_attach<T>(tx: &Sender<Event<T>>) -> impl Fn(&GenericPhidget) + Send + 'static
+where
+    T: Send + 'static,
+{
+    let tx = tx.clone();
+    move |_| {
+        let _ = tx.send(Event::new(EventKind::Attach));
+    }
+}
+
+/// Creates a detach callback that forwards to the channel.
+pub(crate) fn on_detach<T>(tx: &Sender<Event<T>>) -> impl Fn(&GenericPhidget) + Send + 'static
+where
+    T: Send + 'static,
+{
+    let tx = tx.clone();
+    move |_| {
+        let _ = tx.send(Event::new(EventKind::Detach));
+    }
+}
+
+/// Creates an error callback that forwards to the channel.
+pub(crate) fn on_error<T>(
+    tx: &Sender<Event<T>>,
+) -> impl Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static
+where
+    T: Send + 'static,
+{
+    let tx = tx.clone();
+    move |_, code, descr| {
+        let _ = tx.send(Event::new(EventKind::Error(code, descr.into())));
+    }
+}
@@ -43,13 +43,48 @@ pub use crate::errors::*;
 
 /// The main Phidget trait
 pub mod phidget;
-pub use crate::phidget::{AttachCallback, DetachCallback, GenericPhidget, Phidget};
+pub use crate::phidget::{
+    AttachCallback, DetachCallback, DeviceInfo, ErrorCallback, GenericPhidget, Phidget, Property,
+    PropertyChangeCallback,
+};
+
+#[cfg(feature = "async")]
+pub use crate::phidget::OpenAttached;
+
+/// Asynchronous event streams
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "async")]
+pub use crate::stream::EventStream;
+
+/// The Phidget manager for channel discovery
+pub mod manager;
+pub use crate::manager::{ChannelInfo, Manager};
+
+/// Channel addressing
+pub mod selector;
+pub use crate::selector::ChannelSelector;
+
+/// Channel-based event delivery
+pub mod events;
+pub use crate::events::{Event, EventKind};
+
+/// Logging API
+pub mod log;
+pub use crate::log::LogLevel;
 
 /// Network API
 pub mod net;
-pub use crate::net::ServerType;
+pub use crate::net::{NetServer, ServerFlags, ServerInfo, ServerType};
 
 /// Module containing all implemented devices
+///
+/// The bundled phidget22 bindings predate the motor velocity controller
+/// channel class and the expected position properties of the motor
+/// position controller. Until the bindings are regenerated from a newer
+/// `phidget22.h`, there is no `MotorVelocityController`, and
+/// [`MotorPositionController`](crate::devices::MotorPositionController)
+/// has no expected position getters.
 pub mod devices;
 
 // For v0.1.x compatability, sensors available at the root
@@ -92,8 +127,60 @@ pub(crate) fn drop_cb<P: ?Sized>(cb: Option<*mut c_void>) {
     }
 }
 
+// A function that releases a double-boxed callback, like `drop_cb::<P>`
+type DropCbFn = fn(Option<*mut c_void>);
+
+/// The double-boxed callbacks that were replaced on a channel.
+///
+/// The phidget22 event thread may still be running a callback when its
+/// handler is replaced or removed, so the old context can't be released
+/// right away. It is kept here instead, and released when the channel
+/// object is dropped, after the channel has been closed and deleted.
+#[derive(Default)]
+pub(crate) struct RetiredCallbacks(Vec<(*mut c_void, DropCbFn)>);
+
+impl RetiredCallbacks {
+    /// Keeps a replaced callback context, if any, until this is dropped.
+    pub(crate) fn retire<P: ?Sized>(&mut self, cb: Option<*mut c_void>) {
+        if let Some(ctx) = cb {
+            self.0.push((ctx, drop_cb::<P>));
+        }
+    }
+
+    /// Stores the context of a double-boxed callback after attempting to
+    /// register it with the library, given the return code of that call.
+    /// On success, the context that it replaces is retired; on failure,
+    /// the new context, which the library never saw, is released and the
+    /// old one is kept.
+    pub(crate) fn update<P: ?Sized>(
+        &mut self,
+        slot: &mut Option<*mut c_void>,
+        ctx: *mut c_void,
+        rc: c_uint,
+    ) -> Result<()> {
+        match ReturnCode::result(rc) {
+            Ok(()) => {
+                self.retire::<P>(slot.replace(ctx));
+                Ok(())
+            }
+            Err(err) => {
+                drop_cb::<P>(Some(ctx));
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Drop for RetiredCallbacks {
+    fn drop(&mut self) {
+        for (ctx, drop_fn) in self.0.drain(..) {
+            drop_fn(Some(ctx));
+        }
+    }
+}
+
 /// Phidget channel class
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u32)]
 #[allow(missing_docs)]
 pub enum ChannelClass {
@@ -194,8 +281,72 @@ impl TryFrom<u32> for ChannelClass {
     }
 }
 
+/// Phidget channel subclass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+#[allow(missing_docs)]
+pub enum ChannelSubclass {
+    None = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_NONE, // 1
+    DigitalOutputDutyCycle = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_DUTY_CYCLE, // 16
+    DigitalOutputLedDriver = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_LED_DRIVER, // 17
+    DigitalOutputFrequency = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_FREQUENCY, // 18
+    TemperatureSensorRtd = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_TEMPERATURESENSOR_RTD, // 32
+    TemperatureSensorThermocouple =
+        ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_TEMPERATURESENSOR_THERMOCOUPLE, // 33
+    VoltageInputSensorPort = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGEINPUT_SENSOR_PORT, // 48
+    VoltageRatioInputSensorPort =
+        ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGERATIOINPUT_SENSOR_PORT, // 64
+    VoltageRatioInputBridge = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGERATIOINPUT_BRIDGE, // 65
+    LcdGraphic = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_LCD_GRAPHIC, // 80
+    LcdText = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_LCD_TEXT,       // 81
+    EncoderModeSettable = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_ENCODER_MODE_SETTABLE, // 96
+    SpatialAhrs = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_SPATIAL_AHRS, // 112
+}
+
+impl TryFrom<u32> for ChannelSubclass {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use ChannelSubclass::*;
+        match val {
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_NONE => Ok(None), // 1
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_DUTY_CYCLE => {
+                Ok(DigitalOutputDutyCycle)
+            } // 16
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_LED_DRIVER => {
+                Ok(DigitalOutputLedDriver)
+            } // 17
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_DIGITALOUTPUT_FREQUENCY => {
+                Ok(DigitalOutputFrequency)
+            } // 18
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_TEMPERATURESENSOR_RTD => {
+                Ok(TemperatureSensorRtd)
+            } // 32
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_TEMPERATURESENSOR_THERMOCOUPLE => {
+                Ok(TemperatureSensorThermocouple)
+            } // 33
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGEINPUT_SENSOR_PORT => {
+                Ok(VoltageInputSensorPort)
+            } // 48
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGERATIOINPUT_SENSOR_PORT => {
+                Ok(VoltageRatioInputSensorPort)
+            } // 64
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_VOLTAGERATIOINPUT_BRIDGE => {
+                Ok(VoltageRatioInputBridge)
+            } // 65
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_LCD_GRAPHIC => Ok(LcdGraphic), // 80
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_LCD_TEXT => Ok(LcdText), // 81
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_ENCODER_MODE_SETTABLE => {
+                Ok(EncoderModeSettable)
+            } // 96
+            ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_SPATIAL_AHRS => Ok(SpatialAhrs), // 112
+            _ => Err(ReturnCode::InvalidArg),
+        }
+    }
+}
+
 /// Phidget device class
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u32)]
 #[allow(missing_docs)]
 pub enum DeviceClass {
@@ -281,8 +432,70 @@ pub fn library_version_number() -> Result<String> {
 
 #[cfg(test)]
 mod tests {
-    //use super::*;
+    use super::*;
 
     #[test]
     fn it_works() {}
+
+    // Makes a double-boxed callback that holds a clone of the Arc.
+    fn boxed_cb(count: &std::sync::Arc<()>) -> *mut c_void {
+        let count = count.clone();
+        let cb: Box<Box<dyn Fn() + Send>> = Box::new(Box::new(move || {
+            let _ = &count;
+        }));
+        Box::into_raw(cb) as *mut c_void
+    }
+
+    #[test]
+    fn retired_callbacks_released_on_drop() {
+        let count = std::sync::Arc::new(());
+        let mut slot = Some(boxed_cb(&count));
+        let mut retired = RetiredCallbacks::default();
+
+        // A successful update retires the old callback, but keeps it alive
+        let ctx = boxed_cb(&count);
+        assert!(retired
+            .update::<dyn Fn() + Send>(&mut slot, ctx, ReturnCode::Ok as c_uint)
+            .is_ok());
+        assert_eq!(slot, Some(ctx));
+        assert_eq!(std::sync::Arc::strong_count(&count), 3);
+
+        // A failed update releases the new callback right away
+        let ctx = boxed_cb(&count);
+        assert!(retired
+            .update::<dyn Fn() + Send>(&mut slot, ctx, ReturnCode::Unexpected as c_uint)
+            .is_err());
+        assert_ne!(slot, Some(ctx));
+        assert_eq!(std::sync::Arc::strong_count(&count), 3);
+
+        drop(retired);
+        assert_eq!(std::sync::Arc::strong_count(&count), 2);
+        drop_cb::<dyn Fn() + Send>(slot.take());
+        assert_eq!(std::sync::Arc::strong_count(&count), 1);
+    }
+
+    #[test]
+    fn channel_subclass_try_from() {
+        use ChannelSubclass::*;
+        let subclasses = [
+            None,
+            DigitalOutputDutyCycle,
+            DigitalOutputLedDriver,
+            DigitalOutputFrequency,
+            TemperatureSensorRtd,
+            TemperatureSensorThermocouple,
+            VoltageInputSensorPort,
+            VoltageRatioInputSensorPort,
+            VoltageRatioInputBridge,
+            LcdGraphic,
+            LcdText,
+            EncoderModeSettable,
+            SpatialAhrs,
+        ];
+        for subclass in subclasses {
+            assert_eq!(ChannelSubclass::try_from(subclass as u32), Ok(subclass));
+        }
+        assert_eq!(ChannelSubclass::try_from(0), Err(ReturnCode::InvalidArg));
+        assert_eq!(ChannelSubclass::try_from(2), Err(ReturnCode::InvalidArg));
+    }
 }
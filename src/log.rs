@@ -0,0 +1,762 @@
+// phidget-rs/src/log.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+
+//! Phidget logging API
+//!
+//! This wraps the logging facility of the phidget22 library, which writes
+//! the diagnostics of the library, and of the devices, to a file or to the
+//! network.
+//!
+//! With the `log` or `tracing` feature, the records from the Rust
+//! application can be routed into the phidget22 log, so that all of the
+//! hardware diagnostics end up in one place.
+//!
+//! The phidget22 library has no callback for its own log records; they
+//! are only written to its log file or to the network. To go the other
+//! way, a [`LogTail`](crate::log::LogTail) follows the log file as it
+//! grows and parses the new records. With the `log` or `tracing` feature,
+//! a [`LogForwarder`](crate::log::LogForwarder) does this from a
+//! background thread and emits the records into the Rust logging crate.
+//! Records from the [`RUST_SOURCE`](crate::log::RUST_SOURCE) source are
+//! skipped, so they don't loop back.
+
+use crate::{Error, Result, ReturnCode};
+use phidget_sys as ffi;
+use std::{
+    ffi::{CStr, CString},
+    fs::File,
+    io::{self, BufRead, BufReader, Seek, SeekFrom},
+    os::raw::{c_char, c_int},
+    path::{Path, PathBuf},
+    ptr,
+    str::FromStr,
+};
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// The name of the log source used for records from the Rust application.
+pub const RUST_SOURCE: &str = "rust";
+
+/// Phidget log levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum LogLevel {
+    /// Critical errors
+    Critical = ffi::Phidget_LogLevel_PHIDGET_LOG_CRITICAL, // 1
+    /// Errors
+    Error = ffi::Phidget_LogLevel_PHIDGET_LOG_ERROR, // 2
+    /// Warnings
+    Warning = ffi::Phidget_LogLevel_PHIDGET_LOG_WARNING, // 3
+    /// Informational messages
+    Info = ffi::Phidget_LogLevel_PHIDGET_LOG_INFO, // 4
+    /// Debug messages
+    Debug = ffi::Phidget_LogLevel_PHIDGET_LOG_DEBUG, // 5
+    /// Verbose messages, including data from the devices
+    Verbose = ffi::Phidget_LogLevel_PHIDGET_LOG_VERBOSE, // 6
+}
+
+impl TryFrom<u32> for LogLevel {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        use LogLevel::*;
+        match val {
+            ffi::Phidget_LogLevel_PHIDGET_LOG_CRITICAL => Ok(Critical), // 1
+            ffi::Phidget_LogLevel_PHIDGET_LOG_ERROR => Ok(Error),       // 2
+            ffi::Phidget_LogLevel_PHIDGET_LOG_WARNING => Ok(Warning),   // 3
+            ffi::Phidget_LogLevel_PHIDGET_LOG_INFO => Ok(Info),         // 4
+            ffi::Phidget_LogLevel_PHIDGET_LOG_DEBUG => Ok(Debug),       // 5
+            ffi::Phidget_LogLevel_PHIDGET_LOG_VERBOSE => Ok(Verbose),   // 6
+            _ => Err(ReturnCode::UnknownVal),
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = Error;
+
+    /// Parses the name of a level, as written in the phidget22 log file.
+    /// This ignores case, and accepts short names like "Warn".
+    fn from_str(s: &str) -> Result<Self> {
+        use LogLevel::*;
+        match s.to_ascii_lowercase().as_str() {
+            "critical" | "crit" => Ok(Critical),
+            "error" | "err" => Ok(Error),
+            "warning" | "warn" => Ok(Warning),
+            "info" => Ok(Info),
+            "debug" => Ok(Debug),
+            "verbose" | "verb" => Ok(Verbose),
+            _ => Err(ReturnCode::InvalidArg),
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<::log::Level> for LogLevel {
+    fn from(level: ::log::Level) -> Self {
+        use ::log::Level::*;
+        match level {
+            Error => LogLevel::Error,
+            Warn => LogLevel::Warning,
+            Info => LogLevel::Info,
+            Debug => LogLevel::Debug,
+            Trace => LogLevel::Verbose,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<tracing_core::Level> for LogLevel {
+    fn from(level: tracing_core::Level) -> Self {
+        use tracing_core::Level;
+        match level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warning,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Verbose,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<LogLevel> for ::log::Level {
+    fn from(level: LogLevel) -> Self {
+        use ::log::Level;
+        match level {
+            LogLevel::Critical | LogLevel::Error => Level::Error,
+            LogLevel::Warning => Level::Warn,
+            LogLevel::Info => Level::Info,
+            LogLevel::Debug => Level::Debug,
+            LogLevel::Verbose => Level::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<LogLevel> for tracing_core::Level {
+    fn from(level: LogLevel) -> Self {
+        use tracing_core::Level;
+        match level {
+            LogLevel::Critical | LogLevel::Error => Level::ERROR,
+            LogLevel::Warning => Level::WARN,
+            LogLevel::Info => Level::INFO,
+            LogLevel::Debug => Level::DEBUG,
+            LogLevel::Verbose => Level::TRACE,
+        }
+    }
+}
+
+/// The configuration for rotating the log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogRotation {
+    /// The maximum size of the log file, in bytes, before it is rotated
+    pub size: u64,
+    /// The number of rotated log files to keep
+    pub keep_count: u32,
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// Enables logging at the specified level.
+///
+/// The log is written to the file at `destination`, or to the standard
+/// output if no destination is given.
+pub fn enable(level: LogLevel, destination: Option<&Path>) -> Result<()> {
+    let destination = destination
+        .map(|path| {
+            let path = path.to_str().ok_or(ReturnCode::InvalidArg)?;
+            CString::new(path).map_err(|_| ReturnCode::InvalidArg)
+        })
+        .transpose()?;
+    let destination = destination.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+    ReturnCode::result(unsafe { ffi::PhidgetLog_enable(level as u32, destination) })
+}
+
+/// Disables logging.
+pub fn disable() -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_disable() })
+}
+
+/// Enables sending the log to a remote log viewer at the specified address
+/// and port.
+pub fn enable_network(address: &str, port: u16) -> Result<()> {
+    let address = CString::new(address).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe {
+        ffi::PhidgetLog_enableNetwork(address.as_ptr(), c_int::from(port))
+    })
+}
+
+/// Disables sending the log to the network.
+pub fn disable_network() -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_disableNetwork() })
+}
+
+/// Gets the level of the log.
+pub fn level() -> Result<LogLevel> {
+    let mut level: ffi::Phidget_LogLevel = 0;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_getLevel(&mut level) })?;
+    LogLevel::try_from(level)
+}
+
+/// Sets the level of the log.
+/// This applies to all of the log sources.
+pub fn set_level(level: LogLevel) -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_setLevel(level as u32) })
+}
+
+/// Writes a message to the log at the specified level.
+pub fn log(level: LogLevel, msg: &str) -> Result<()> {
+    let msg = CString::new(msg).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_logs(level as u32, msg.as_ptr()) })
+}
+
+/// Writes a message to the log at the specified level, from the
+/// specified source.
+pub fn log_source(level: LogLevel, source: &str, msg: &str) -> Result<()> {
+    let source = CString::new(source).map_err(|_| ReturnCode::InvalidArg)?;
+    let msg = CString::new(msg).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe {
+        ffi::PhidgetLog_loges(level as u32, source.as_ptr(), msg.as_ptr())
+    })
+}
+
+// ----- Sources -----
+
+/// Adds a new log source, with the specified level.
+pub fn add_source(source: &str, level: LogLevel) -> Result<()> {
+    let source = CString::new(source).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_addSource(source.as_ptr(), level as u32) })
+}
+
+/// Gets the names of all the log sources.
+pub fn sources() -> Result<Vec<String>> {
+    // Ask for the number of sources, then read them
+    let mut count = 0;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_getSources(ptr::null_mut(), &mut count) })?;
+
+    let mut srcs: Vec<*const c_char> = vec![ptr::null(); count as usize];
+    ReturnCode::result(unsafe { ffi::PhidgetLog_getSources(srcs.as_mut_ptr(), &mut count) })?;
+
+    Ok(srcs
+        .into_iter()
+        .take(count as usize)
+        .filter(|src| !src.is_null())
+        .map(|src| unsafe { CStr::from_ptr(src) }.to_string_lossy().into())
+        .collect())
+}
+
+/// Gets the level of the specified log source.
+pub fn source_level(source: &str) -> Result<LogLevel> {
+    let source = CString::new(source).map_err(|_| ReturnCode::InvalidArg)?;
+    let mut level: ffi::Phidget_LogLevel = 0;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_getSourceLevel(source.as_ptr(), &mut level) })?;
+    LogLevel::try_from(level)
+}
+
+/// Sets the level of the specified log source.
+pub fn set_source_level(source: &str, level: LogLevel) -> Result<()> {
+    let source = CString::new(source).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_setSourceLevel(source.as_ptr(), level as u32) })
+}
+
+// ----- Rotation -----
+
+/// Rotates the log file immediately.
+pub fn rotate() -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_rotate() })
+}
+
+/// Enables rotation of the log file.
+pub fn enable_rotating() -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_enableRotating() })
+}
+
+/// Disables rotation of the log file.
+pub fn disable_rotating() -> Result<()> {
+    ReturnCode::result(unsafe { ffi::PhidgetLog_disableRotating() })
+}
+
+/// Determines if rotation of the log file is enabled.
+pub fn is_rotating() -> Result<bool> {
+    let mut value = 0;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_isRotating(&mut value) })?;
+    Ok(value != 0)
+}
+
+/// Gets the configuration for rotating the log file.
+pub fn rotating() -> Result<LogRotation> {
+    let mut size = 0;
+    let mut keep_count = 0;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_getRotating(&mut size, &mut keep_count) })?;
+    Ok(LogRotation {
+        size,
+        keep_count: keep_count as u32,
+    })
+}
+
+/// Sets the configuration for rotating the log file.
+/// This does not enable rotation by itself.
+pub fn set_rotating(rotation: LogRotation) -> Result<()> {
+    let keep_count = c_int::try_from(rotation.keep_count).map_err(|_| ReturnCode::InvalidArg)?;
+    ReturnCode::result(unsafe { ffi::PhidgetLog_setRotating(rotation.size, keep_count) })
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Reading the log file
+
+/// A record read back from the phidget22 log file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The time at which the record was written, as it appears in the file
+    pub timestamp: String,
+    /// The level of the record
+    pub level: LogLevel,
+    /// The source of the record, if it has one
+    pub source: Option<String>,
+    /// The message
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Parses a line of the phidget22 log file.
+    ///
+    /// The lines have the form:
+    ///
+    /// ```text
+    /// <timestamp> <Level> [source][location]: message
+    /// ```
+    ///
+    /// where the bracketed groups are optional. Only the first of them is
+    /// taken as the source. This returns `None` if the line doesn't have a
+    /// recognized level, such as the continuation of a multi-line message.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let open = line.find('<')?;
+        let close = open + line[open..].find('>')?;
+        let level = line[open + 1..close].parse().ok()?;
+        let timestamp = line[..open].trim().to_string();
+
+        let mut rest = line[close + 1..].trim_start();
+        let mut source = None;
+        while let Some(group) = rest.strip_prefix('[') {
+            let end = group.find(']')?;
+            source.get_or_insert_with(|| group[..end].to_string());
+            rest = &group[end + 1..];
+        }
+        let rest = rest.strip_prefix(':').unwrap_or(rest);
+
+        Some(Self {
+            timestamp,
+            level,
+            source,
+            message: rest.trim().to_string(),
+        })
+    }
+}
+
+/// Follows the phidget22 log file as it grows, reading the new records.
+///
+/// The reader never blocks waiting for new records; it returns what has
+/// been written since the last read, and can be polled again later. If
+/// the file gets shorter, because it was rotated or truncated, the reader
+/// starts again from the beginning of the file at the same path.
+#[derive(Debug)]
+pub struct LogTail {
+    // The path to the log file
+    path: PathBuf,
+    // The open log file
+    file: BufReader<File>,
+    // The position just past the last complete line that was read
+    pos: u64,
+    // A line that has only been partly written
+    partial: String,
+}
+
+impl LogTail {
+    /// Opens the log file at `path`, positioned at its end, so that only
+    /// the records written from now on are read.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut tail = Self::open_from_start(path)?;
+        tail.pos = tail.file.seek(SeekFrom::End(0))?;
+        Ok(tail)
+    }
+
+    /// Opens the log file at `path`, positioned at its start, so that the
+    /// records already in the file are read as well.
+    pub fn open_from_start<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = BufReader::new(File::open(&path)?);
+        Ok(Self {
+            path,
+            file,
+            pos: 0,
+            partial: String::new(),
+        })
+    }
+
+    /// Gets the path to the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the next complete line that was written to the log file, if
+    /// there is one.
+    pub fn next_line(&mut self) -> io::Result<Option<String>> {
+        loop {
+            let n = self.file.read_line(&mut self.partial)?;
+            if n == 0 {
+                if self.reopen_if_shorter()? {
+                    continue;
+                }
+                return Ok(None);
+            }
+            if !self.partial.ends_with('\n') {
+                // The rest of the line hasn't been written yet
+                continue;
+            }
+            self.pos += self.partial.len() as u64;
+            let line = std::mem::take(&mut self.partial);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if !line.is_empty() {
+                return Ok(Some(line.to_string()));
+            }
+        }
+    }
+
+    /// Reads the next record that was written to the log file, if there
+    /// is one.
+    ///
+    /// Lines that don't parse as a record, like the continuation of a
+    /// multi-line message, are returned as an `Info` record with no
+    /// timestamp or source.
+    pub fn next_record(&mut self) -> io::Result<Option<LogRecord>> {
+        Ok(self.next_line()?.map(|line| {
+            LogRecord::parse(&line).unwrap_or(LogRecord {
+                timestamp: String::new(),
+                level: LogLevel::Info,
+                source: None,
+                message: line,
+            })
+        }))
+    }
+
+    // Starts again at the beginning of the file at the path if it is
+    // shorter than what was already read, returning whether it did.
+    fn reopen_if_shorter(&mut self) -> io::Result<bool> {
+        let len = std::fs::metadata(&self.path)?.len();
+        if len >= self.pos + self.partial.len() as u64 {
+            return Ok(false);
+        }
+        self.file = BufReader::new(File::open(&self.path)?);
+        self.pos = 0;
+        self.partial.clear();
+        Ok(true)
+    }
+}
+
+impl Iterator for LogTail {
+    type Item = io::Result<LogRecord>;
+
+    /// Gets the next record, returning `None` when there are no more
+    /// records in the file for now.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+/// A background thread that follows the phidget22 log file and emits its
+/// records into the `log` or `tracing` crate.
+///
+/// Records from the [`RUST_SOURCE`] source are skipped, since they came
+/// from the application in the first place. The thread is stopped when
+/// the forwarder is dropped.
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[derive(Debug)]
+pub struct LogForwarder {
+    // Tells the thread to stop
+    done: Arc<AtomicBool>,
+    // The forwarding thread
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl LogForwarder {
+    /// Starts forwarding the new records from the log file into the `log`
+    /// crate, checking the file for new records at the specified interval.
+    ///
+    /// The records are logged with a target of `phidget22::<source>`.
+    #[cfg(feature = "log")]
+    pub fn to_log(tail: LogTail, interval: Duration) -> Self {
+        Self::spawn(tail, interval, |rec| {
+            let target = match &rec.source {
+                Some(src) => format!("phidget22::{}", src),
+                None => "phidget22".to_string(),
+            };
+            ::log::log!(target: &target, rec.level.into(), "{}", rec.message);
+        })
+    }
+
+    /// Starts forwarding the new records from the log file into the
+    /// `tracing` crate, checking the file for new records at the specified
+    /// interval.
+    ///
+    /// The events have a target of `phidget22`, with the source of the
+    /// record in a `source` field.
+    #[cfg(feature = "tracing")]
+    pub fn to_tracing(tail: LogTail, interval: Duration) -> Self {
+        use tracing::Level;
+        Self::spawn(tail, interval, |rec| {
+            let source = rec.source.as_deref().unwrap_or_default();
+            let msg = &rec.message;
+            match Level::from(rec.level) {
+                Level::ERROR => tracing::error!(target: "phidget22", source, "{}", msg),
+                Level::WARN => tracing::warn!(target: "phidget22", source, "{}", msg),
+                Level::INFO => tracing::info!(target: "phidget22", source, "{}", msg),
+                Level::DEBUG => tracing::debug!(target: "phidget22", source, "{}", msg),
+                _ => tracing::trace!(target: "phidget22", source, "{}", msg),
+            }
+        })
+    }
+
+    // Starts the thread that reads the records and passes them to `emit`.
+    fn spawn(mut tail: LogTail, interval: Duration, emit: fn(&LogRecord)) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        let thr_done = Arc::clone(&done);
+        let thread = thread::spawn(move || {
+            while !thr_done.load(Ordering::Acquire) {
+                // A read error is retried at the next interval
+                while let Some(Ok(rec)) = tail.next() {
+                    if rec.source.as_deref() != Some(RUST_SOURCE) {
+                        emit(&rec);
+                    }
+                }
+                thread::park_timeout(interval);
+            }
+        });
+        Self {
+            done,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl Drop for LogForwarder {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+// Registers the source for the records from the Rust application,
+// if it is not already registered.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn add_rust_source(level: LogLevel) -> Result<()> {
+    match add_source(RUST_SOURCE, level) {
+        Err(ReturnCode::Exist) => set_source_level(RUST_SOURCE, level),
+        res => res,
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Bridge from the 'log' crate
+
+/// A logger for the `log` crate that writes the records into the
+/// phidget22 log, under the [`RUST_SOURCE`] source.
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhidgetLogger;
+
+#[cfg(feature = "log")]
+static LOGGER: PhidgetLogger = PhidgetLogger;
+
+#[cfg(feature = "log")]
+impl ::log::Log for PhidgetLogger {
+    /// Checks the record's level against the level of the [`RUST_SOURCE`]
+    /// source in the phidget22 log, or the overall log level if the source
+    /// isn't registered.
+    fn enabled(&self, metadata: &::log::Metadata) -> bool {
+        source_level(RUST_SOURCE)
+            .or_else(|_| level())
+            .is_ok_and(|lvl| LogLevel::from(metadata.level()) <= lvl)
+    }
+
+    fn log(&self, record: &::log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let msg = format!("{}: {}", record.target(), record.args());
+        // There's nowhere to report a failure to log
+        let _ = log_source(record.level().into(), RUST_SOURCE, &msg);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs a [`PhidgetLogger`] as the logger for the `log` crate, with
+/// the specified maximum level.
+///
+/// The phidget22 log must also be enabled, with [`enable()`], for the
+/// records to be written anywhere.
+#[cfg(feature = "log")]
+pub fn init_logger(level: ::log::LevelFilter) -> Result<()> {
+    let phidget_level = level.to_level().map_or(LogLevel::Critical, LogLevel::from);
+    add_rust_source(phidget_level)?;
+    ::log::set_logger(&LOGGER).map_err(|_| ReturnCode::Exist)?;
+    ::log::set_max_level(level);
+    Ok(())
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Bridge from the 'tracing' crate
+
+/// A `tracing` layer that writes the events into the phidget22 log,
+/// under the [`RUST_SOURCE`] source.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy)]
+pub struct PhidgetLayer;
+
+#[cfg(feature = "tracing")]
+impl PhidgetLayer {
+    /// Creates a new layer, registering the Rust log source at the
+    /// specified level.
+    ///
+    /// The phidget22 log must also be enabled, with [`enable()`], for the
+    /// events to be written anywhere.
+    pub fn new(level: LogLevel) -> Result<Self> {
+        add_rust_source(level)?;
+        Ok(Self)
+    }
+}
+
+// Formats the fields of a tracing event into a log message.
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct MessageVisitor(String);
+
+#[cfg(feature = "tracing")]
+impl tracing_core::field::Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = if field.name() == "message" {
+            write!(self.0, "{:?}", value)
+        }
+        else {
+            write!(self.0, "{}={:?}", field.name(), value)
+        };
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<S: tracing_core::Subscriber> tracing_subscriber::Layer<S> for PhidgetLayer {
+    fn on_event(
+        &self,
+        event: &tracing_core::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let meta = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let msg = format!("{}: {}", meta.target(), visitor.0);
+        // There's nowhere to report a failure to log
+        let _ = log_source((*meta.level()).into(), RUST_SOURCE, &msg);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_level_try_from() {
+        use LogLevel::*;
+        for level in [Critical, Error, Warning, Info, Debug, Verbose] {
+            assert_eq!(LogLevel::try_from(level as u32), Ok(level));
+        }
+        assert_eq!(LogLevel::try_from(0), Err(ReturnCode::UnknownVal));
+        assert_eq!(LogLevel::try_from(7), Err(ReturnCode::UnknownVal));
+    }
+
+    #[test]
+    fn log_level_from_str() {
+        assert_eq!("Info".parse(), Ok(LogLevel::Info));
+        assert_eq!("WARN".parse(), Ok(LogLevel::Warning));
+        assert_eq!("crit".parse(), Ok(LogLevel::Critical));
+        assert_eq!("loud".parse::<LogLevel>(), Err(ReturnCode::InvalidArg));
+    }
+
+    #[test]
+    fn log_record_parse() {
+        let rec = LogRecord::parse(
+            "2023-05-01T12:00:00 <Warning> [phidget22][usb.c+123 open()]: device busy\n",
+        )
+        .unwrap();
+        assert_eq!(rec.timestamp, "2023-05-01T12:00:00");
+        assert_eq!(rec.level, LogLevel::Warning);
+        assert_eq!(rec.source.as_deref(), Some("phidget22"));
+        assert_eq!(rec.message, "device busy");
+
+        let rec = LogRecord::parse("12:00:00 <Debug> no source here").unwrap();
+        assert_eq!(rec.level, LogLevel::Debug);
+        assert_eq!(rec.source, None);
+        assert_eq!(rec.message, "no source here");
+
+        assert_eq!(LogRecord::parse("    continued message"), None);
+        assert_eq!(LogRecord::parse("12:00:00 <Loud> message"), None);
+    }
+
+    #[test]
+    fn log_tail_follows_file() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("phidget-log-{}.log", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "t0 <Info> [phidget22]: before").unwrap();
+
+        let mut tail = LogTail::open(&path).unwrap();
+        assert!(tail.next_record().unwrap().is_none());
+
+        // A partly written line is held back until it is complete
+        write!(file, "t1 <Error> [phidget22]: af").unwrap();
+        assert!(tail.next_record().unwrap().is_none());
+        writeln!(file, "ter").unwrap();
+        let rec = tail.next_record().unwrap().unwrap();
+        assert_eq!(rec.level, LogLevel::Error);
+        assert_eq!(rec.message, "after");
+
+        // Truncating the file starts again from the beginning
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "t2 <Info> [rust]: new").unwrap();
+        let recs: Vec<_> = tail.by_ref().map(|rec| rec.unwrap().message).collect();
+        assert_eq!(recs, vec!["new"]);
+
+        drop(file);
+        let _ = std::fs::remove_file(&path);
+    }
+}
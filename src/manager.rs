@@ -0,0 +1,273 @@
+// phidget-rs/src/manager.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+//! The Phidget Manager.
+//!
+//! The manager is used to discover the Phidget channels that are available
+//! to the application. It reports an attach event for each channel that is
+//! already connected when it is opened, and then for every channel that is
+//! plugged in afterward. Likewise, it reports a detach event whenever a
+//! channel goes away.
+//!
+
+use crate::{ChannelClass, DeviceClass, GenericPhidget, Phidget, Result, ReturnCode};
+use phidget_sys::{self as ffi, PhidgetHandle, PhidgetManagerHandle};
+use std::{
+    os::raw::c_void,
+    ptr,
+    sync::{Arc, Mutex},
+};
+
+/// The signature for the manager attach and detach callbacks.
+pub type ManagerCallback = dyn Fn(&GenericPhidget, &ChannelInfo) + Send + Sync + 'static;
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// The identity of a single Phidget channel.
+///
+/// This is an owned snapshot of the properties that uniquely identify a
+/// channel, which can be kept after the underlying handle is gone, and
+/// used to select the channel when opening a device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChannelInfo {
+    /// The serial number of the device (or the VINT Hub it's attached to)
+    pub serial_number: i32,
+    /// The VINT Hub port to which the device is attached
+    pub hub_port: i32,
+    /// Whether the channel is a VINT Hub port, itself
+    pub is_hub_port_device: bool,
+    /// The channel index on the device
+    pub channel: i32,
+    /// The class of the channel
+    pub channel_class: ChannelClass,
+    /// The name of the channel
+    pub channel_name: String,
+    /// The class of the device
+    pub device_class: DeviceClass,
+    /// The SKU (part number) of the device
+    pub device_sku: String,
+    /// The name of the device
+    pub device_name: String,
+}
+
+impl ChannelInfo {
+    /// Reads the identity of the channel from a Phidget.
+    pub fn new<P: Phidget + ?Sized>(ph: &mut P) -> Result<Self> {
+        Ok(Self {
+            serial_number: ph.serial_number()?,
+            hub_port: ph.hub_port()?,
+            is_hub_port_device: ph.is_hub_port_device()?,
+            channel: ph.channel()?,
+            channel_class: ph.channel_class()?,
+            channel_name: ph.channel_name()?,
+            device_class: ph.device_class()?,
+            device_sku: ph.device_sku()?,
+            device_name: ph.device_name()?,
+        })
+    }
+
+    /// Applies the address of this channel as the filters for a Phidget
+    /// that has not yet been opened, so that it opens this channel.
+    pub fn apply_to<P: Phidget + ?Sized>(&self, ph: &mut P) -> Result<()> {
+        ph.set_serial_number(self.serial_number)?;
+        ph.set_is_hub_port_device(self.is_hub_port_device)?;
+        ph.set_hub_port(self.hub_port)?;
+        ph.set_channel(self.channel)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+// The state shared with the low-level manager callbacks.
+#[derive(Default)]
+struct ManagerState {
+    // The channels that are currently attached
+    channels: Mutex<Vec<ChannelInfo>>,
+    // The user's attach callback, if registered
+    attach_cb: Mutex<Option<Arc<ManagerCallback>>>,
+    // The user's detach callback, if registered
+    detach_cb: Mutex<Option<Arc<ManagerCallback>>>,
+}
+
+impl ManagerState {
+    // Gets a copy of a user callback, so that it can be called without
+    // holding the lock. The callback is then free to replace the handlers.
+    fn callback(cb: &Mutex<Option<Arc<ManagerCallback>>>) -> Option<Arc<ManagerCallback>> {
+        cb.lock().ok().and_then(|cb| cb.clone())
+    }
+}
+
+/// The Phidget Manager.
+///
+/// This owns a handle to a phidget22 manager, and keeps a live list of all
+/// the channels that are currently attached. The list can be queried at
+/// any time with [`Manager::channels()`], and the application can also
+/// register callbacks to be notified as channels come and go.
+pub struct Manager {
+    // Handle to the manager in the phidget22 library
+    mgr: PhidgetManagerHandle,
+    // The state shared with the low-level callbacks
+    state: Arc<ManagerState>,
+}
+
+impl Manager {
+    /// Creates a new manager.
+    ///
+    /// The manager must be opened before it will report any channels.
+    ///
+    /// If the phidget22 library fails to create the manager, the handle is
+    /// left null, and the library will return an error from any call that
+    /// uses it, such as `open()`.
+    pub fn new() -> Self {
+        let mut mgr: PhidgetManagerHandle = ptr::null_mut();
+        let state = Arc::new(ManagerState::default());
+        let ctx = Arc::as_ptr(&state) as *mut c_void;
+        unsafe {
+            ffi::PhidgetManager_create(&mut mgr);
+            ffi::PhidgetManager_setOnAttachHandler(mgr, Some(Self::on_attach), ctx);
+            ffi::PhidgetManager_setOnDetachHandler(mgr, Some(Self::on_detach), ctx);
+        }
+        Self { mgr, state }
+    }
+
+    // Low-level, unsafe, callback for channel attach events.
+    // The context is a pointer to the shared manager state.
+    unsafe extern "C" fn on_attach(
+        _mgr: PhidgetManagerHandle,
+        ctx: *mut c_void,
+        phid: PhidgetHandle,
+    ) {
+        if !ctx.is_null() {
+            let state: &ManagerState = &*(ctx as *const _);
+            let mut ph = GenericPhidget::from(phid);
+            if let Ok(info) = ChannelInfo::new(&mut ph) {
+                if let Ok(mut channels) = state.channels.lock() {
+                    channels.push(info.clone());
+                }
+                if let Some(cb) = ManagerState::callback(&state.attach_cb) {
+                    cb(&ph, &info);
+                }
+            }
+        }
+    }
+
+    // Low-level, unsafe, callback for channel detach events.
+    // The context is a pointer to the shared manager state.
+    unsafe extern "C" fn on_detach(
+        _mgr: PhidgetManagerHandle,
+        ctx: *mut c_void,
+        phid: PhidgetHandle,
+    ) {
+        if !ctx.is_null() {
+            let state: &ManagerState = &*(ctx as *const _);
+            let mut ph = GenericPhidget::from(phid);
+            if let Ok(info) = ChannelInfo::new(&mut ph) {
+                if let Ok(mut channels) = state.channels.lock() {
+                    if let Some(i) = channels.iter().position(|ch| *ch == info) {
+                        channels.remove(i);
+                    }
+                }
+                if let Some(cb) = ManagerState::callback(&state.detach_cb) {
+                    cb(&ph, &info);
+                }
+            }
+        }
+    }
+
+    /// Get a reference to the underlying manager handle
+    pub fn as_handle(&self) -> &PhidgetManagerHandle {
+        &self.mgr
+    }
+
+    /// Opens the manager.
+    ///
+    /// Once opened, the manager will report an attach event for every
+    /// channel that is already connected to the system.
+    pub fn open(&mut self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetManager_open(self.mgr) })
+    }
+
+    /// Closes the manager.
+    ///
+    /// This clears the list of attached channels.
+    pub fn close(&mut self) -> Result<()> {
+        ReturnCode::result(unsafe { ffi::PhidgetManager_close(self.mgr) })?;
+        if let Ok(mut channels) = self.state.channels.lock() {
+            channels.clear();
+        }
+        Ok(())
+    }
+
+    /// Gets a snapshot of all the channels that are currently attached.
+    pub fn channels(&self) -> Vec<ChannelInfo> {
+        self.state
+            .channels
+            .lock()
+            .map(|channels| channels.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets a snapshot of the attached channels of a specific class.
+    pub fn channels_of_class(&self, cls: ChannelClass) -> Vec<ChannelInfo> {
+        self.channels()
+            .into_iter()
+            .filter(|ch| ch.channel_class == cls)
+            .collect()
+    }
+
+    /// Sets a handler to receive channel attach callbacks.
+    ///
+    /// This should be set before the manager is opened, otherwise the
+    /// events for channels that were already connected will be missed.
+    pub fn set_on_attach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, &ChannelInfo) + Send + Sync + 'static,
+    {
+        let mut attach_cb = self
+            .state
+            .attach_cb
+            .lock()
+            .map_err(|_| ReturnCode::Unexpected)?;
+        *attach_cb = Some(Arc::new(cb));
+        Ok(())
+    }
+
+    /// Sets a handler to receive channel detach callbacks.
+    pub fn set_on_detach_handler<F>(&mut self, cb: F) -> Result<()>
+    where
+        F: Fn(&GenericPhidget, &ChannelInfo) + Send + Sync + 'static,
+    {
+        let mut detach_cb = self
+            .state
+            .detach_cb
+            .lock()
+            .map_err(|_| ReturnCode::Unexpected)?;
+        *detach_cb = Some(Arc::new(cb));
+        Ok(())
+    }
+}
+
+unsafe impl Send for Manager {}
+
+impl Default for Manager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Manager {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::PhidgetManager_close(self.mgr);
+            ffi::PhidgetManager_delete(&mut self.mgr);
+        }
+    }
+}
@@ -13,11 +13,17 @@
 //!
 
 use crate::{Error, Result, ReturnCode};
-use phidget_sys as ffi;
-use std::{ffi::CString, os::raw::c_int};
+use phidget_sys::{self as ffi, PhidgetServerHandle};
+use std::{
+    ffi::{CStr, CString},
+    ops::{BitAnd, BitOr, BitOrAssign},
+    os::raw::{c_char, c_int, c_void},
+    ptr,
+    sync::{Arc, Mutex},
+};
 
 /// Phidget server types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u32)]
 #[allow(missing_docs)]
 pub enum ServerType {
@@ -50,26 +56,145 @@ impl TryFrom<u32> for ServerType {
     }
 }
 
+/// The flags for a Phidget network server.
+///
+/// These are passed when adding, disabling, or starting a server, and are
+/// reported in the [`ServerInfo`] for a server. The flags can be combined
+/// with the `|` operator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServerFlags(u32);
+
+impl ServerFlags {
+    /// No flags
+    pub const NONE: Self = Self(0);
+    /// Clients need a password to connect to the server
+    pub const AUTH_REQUIRED: Self = Self(ffi::PHIDGETSERVER_AUTHREQUIRED);
+
+    /// Creates a set of flags from the raw phidget22 value.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Gets the raw phidget22 value of the flags.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Determines if no flags are set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Determines if all of the `other` flags are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // The value to pass to the phidget22 functions.
+    fn as_raw(self) -> c_int {
+        self.0 as c_int
+    }
+}
+
+impl BitOr for ServerFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ServerFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for ServerFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// A Phidget network server, as reported by the phidget22 library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServerInfo {
+    /// The name of the server
+    pub name: String,
+    /// The service type that the server publishes, like
+    /// "_phidget22server._tcp"
+    pub stype: String,
+    /// The type of the server
+    pub server_type: ServerType,
+    /// The host name of the server
+    pub host: String,
+    /// The port on which the server listens
+    pub port: i32,
+    /// The network address of the server
+    pub address: String,
+    /// The flags for the server
+    pub flags: ServerFlags,
+    /// Whether clients need a password to connect to the server
+    pub password_protected: bool,
+}
+
+impl ServerInfo {
+    // Reads the info from a server handle from the phidget22 library.
+    unsafe fn from_handle(server: PhidgetServerHandle) -> Option<Self> {
+        // Copies a (possibly null) C string into an owned string.
+        unsafe fn to_string(s: *const c_char) -> String {
+            if s.is_null() {
+                String::new()
+            }
+            else {
+                CStr::from_ptr(s).to_string_lossy().into()
+            }
+        }
+
+        if server.is_null() {
+            return None;
+        }
+        let server = &*server;
+        let flags = ServerFlags::from_bits(server.flags as u32);
+        Some(Self {
+            name: to_string(server.name),
+            stype: to_string(server.stype),
+            server_type: ServerType::try_from(server.type_).unwrap_or(ServerType::None),
+            host: to_string(server.host),
+            port: server.port as i32,
+            address: to_string(server.addr),
+            flags,
+            password_protected: flags.contains(ServerFlags::AUTH_REQUIRED),
+        })
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////
 
 /// Register a server to which the client will try to connect.
+///
+/// This installs the server event handlers, so that the server is
+/// included in [`servers()`] once the library reports it.
 pub fn add_server(
     server_name: &str,
     address: &str,
     port: i32,
     password: &str,
-    flags: i32,
+    flags: ServerFlags,
 ) -> Result<()> {
     let server_name = CString::new(server_name).unwrap();
     let address = CString::new(address).unwrap();
     let password = CString::new(password).unwrap();
+    install_server_handlers()?;
     ReturnCode::result(unsafe {
         ffi::PhidgetNet_addServer(
             server_name.as_ptr(),
             address.as_ptr(),
             port as c_int,
             password.as_ptr(),
-            flags as c_int,
+            flags.as_raw(),
         )
     })
 }
@@ -93,10 +218,10 @@ pub fn enable_server(server_name: &str) -> Result<()> {
 }
 
 /// Prevents attempts to automatically connect to a server.
-pub fn disable_server(server_name: &str, flags: i32) -> Result<()> {
+pub fn disable_server(server_name: &str, flags: ServerFlags) -> Result<()> {
     let server_name = CString::new(server_name).unwrap();
     ReturnCode::result(unsafe {
-        ffi::PhidgetNet_disableServer(server_name.as_ptr(), flags as c_int)
+        ffi::PhidgetNet_disableServer(server_name.as_ptr(), flags.as_raw())
     })
 }
 
@@ -116,6 +241,7 @@ pub fn set_server_passward(server_name: &str, password: &str) -> Result<()> {
 /// the network.
 /// Currently Multicast DNS is used to discover and publish Phidget servers.
 pub fn enable_server_discovery(server_type: ServerType) -> Result<()> {
+    install_server_handlers()?;
     ReturnCode::result(unsafe { ffi::PhidgetNet_enableServerDiscovery(server_type as u32) })
 }
 
@@ -125,55 +251,365 @@ pub fn disable_server_discovery(server_type: ServerType) -> Result<()> {
     ReturnCode::result(unsafe { ffi::PhidgetNet_disableServerDiscovery(server_type as u32) })
 }
 
-/*
-pub type PhidgetNet_OnServerAddedCallback = ::std::option::Option<
-    unsafe extern "C" fn(
-        ctx: *mut ::std::os::raw::c_void,
-        server: PhidgetServerHandle,
-        kv: *mut ::std::os::raw::c_void,
-    ),
->;
-pub type PhidgetNet_OnServerRemovedCallback = ::std::option::Option<
-    unsafe extern "C" fn(ctx: *mut ::std::os::raw::c_void, server: PhidgetServerHandle),
->;
-extern "C" {
-    pub fn PhidgetNet_setOnServerAddedHandler(
-        fptr: PhidgetNet_OnServerAddedCallback,
-        ctx: *mut ::std::os::raw::c_void,
-    ) -> PhidgetReturnCode;
-}
-extern "C" {
-    pub fn PhidgetNet_setOnServerRemovedHandler(
-        fptr: PhidgetNet_OnServerRemovedCallback,
-        ctx: *mut ::std::os::raw::c_void,
-    ) -> PhidgetReturnCode;
-}
-extern "C" {
-    pub fn PhidgetNet_getServerAddressList(
-        hostname: *const ::std::os::raw::c_char,
-        addressFamily: ::std::os::raw::c_int,
-        addressList: *mut *mut ::std::os::raw::c_char,
-        count: *mut u32,
-    ) -> PhidgetReturnCode;
-}
-extern "C" {
-    pub fn PhidgetNet_freeServerAddressList(
-        addressList: *mut *mut ::std::os::raw::c_char,
-        count: u32,
-    ) -> PhidgetReturnCode;
-}
-extern "C" {
-    pub fn PhidgetNet_startServer(
-        flags: ::std::os::raw::c_int,
-        addressFamily: ::std::os::raw::c_int,
-        serverName: *const ::std::os::raw::c_char,
-        address: *const ::std::os::raw::c_char,
-        port: ::std::os::raw::c_int,
-        password: *const ::std::os::raw::c_char,
-        server: *mut PhidgetServerHandle,
-    ) -> PhidgetReturnCode;
-}
-extern "C" {
-    pub fn PhidgetNet_stopServer(server: *mut PhidgetServerHandle) -> PhidgetReturnCode;
-}
-*/
+// ----- Server discovery events -----
+
+/// The signature for the server added and removed callbacks.
+pub type ServerCallback = dyn Fn(&ServerInfo) + Send + Sync + 'static;
+
+// The servers that have been reported by the library, and not yet removed
+static SERVERS: Mutex<Vec<ServerInfo>> = Mutex::new(Vec::new());
+
+// The user's server added callback, if registered
+static SERVER_ADDED_CB: Mutex<Option<Arc<ServerCallback>>> = Mutex::new(None);
+
+// The user's server removed callback, if registered
+static SERVER_REMOVED_CB: Mutex<Option<Arc<ServerCallback>>> = Mutex::new(None);
+
+// Calls the user's callback, if any, with the server info.
+// The callback is cloned out of the lock so that it can set the handlers.
+fn call_server_cb(cb: &Mutex<Option<Arc<ServerCallback>>>, info: &ServerInfo) {
+    let cb = cb.lock().ok().and_then(|cb| cb.clone());
+    if let Some(cb) = cb {
+        cb(info);
+    }
+}
+
+// Low-level, unsafe, callback for server added events.
+// The key/value pairs are an opaque handle in the public phidget22 API,
+// so they are not decoded.
+unsafe extern "C" fn on_server_added(
+    _ctx: *mut c_void,
+    server: PhidgetServerHandle,
+    _kv: *mut c_void,
+) {
+    if let Some(info) = ServerInfo::from_handle(server) {
+        if let Ok(mut servers) = SERVERS.lock() {
+            insert_server(&mut servers, info.clone());
+        }
+        call_server_cb(&SERVER_ADDED_CB, &info);
+    }
+}
+
+// Low-level, unsafe, callback for server removed events.
+unsafe extern "C" fn on_server_removed(_ctx: *mut c_void, server: PhidgetServerHandle) {
+    if let Some(info) = ServerInfo::from_handle(server) {
+        if let Ok(mut servers) = SERVERS.lock() {
+            remove_server_named(&mut servers, &info.name);
+        }
+        call_server_cb(&SERVER_REMOVED_CB, &info);
+    }
+}
+
+// Adds a server to the list of known servers, replacing any previous
+// entry with the same name.
+fn insert_server(servers: &mut Vec<ServerInfo>, info: ServerInfo) {
+    remove_server_named(servers, &info.name);
+    servers.push(info);
+}
+
+// Removes a server from the list of known servers, by name.
+fn remove_server_named(servers: &mut Vec<ServerInfo>, name: &str) {
+    servers.retain(|srv| srv.name != name);
+}
+
+// Installs the low-level server callbacks, which keep the list of
+// known servers up to date.
+fn install_server_handlers() -> Result<()> {
+    ReturnCode::result(unsafe {
+        ffi::PhidgetNet_setOnServerAddedHandler(Some(on_server_added), ptr::null_mut())
+    })?;
+    ReturnCode::result(unsafe {
+        ffi::PhidgetNet_setOnServerRemovedHandler(Some(on_server_removed), ptr::null_mut())
+    })
+}
+
+/// Gets a snapshot of the servers that are currently known.
+///
+/// This contains the servers that the library has reported, and not
+/// since removed, after either discovery was enabled with
+/// `enable_server_discovery()`, a server was registered with
+/// `add_server()`, or a server handler was set. Servers reported before
+/// the first of these are not included.
+pub fn servers() -> Vec<ServerInfo> {
+    SERVERS
+        .lock()
+        .map(|servers| servers.clone())
+        .unwrap_or_default()
+}
+
+/// Sets a handler to receive callbacks when a server is discovered.
+///
+/// Servers are only discovered after enabling discovery with
+/// `enable_server_discovery()`.
+///
+/// The server's key/value dictionary is not available: phidget22 only
+/// passes it as an opaque handle, so the handler gets the [`ServerInfo`]
+/// fields alone.
+pub fn set_on_server_added_handler<F>(cb: F) -> Result<()>
+where
+    F: Fn(&ServerInfo) + Send + Sync + 'static,
+{
+    *SERVER_ADDED_CB.lock().map_err(|_| ReturnCode::Unexpected)? = Some(Arc::new(cb));
+    install_server_handlers()
+}
+
+/// Sets a handler to receive callbacks when a server goes away.
+pub fn set_on_server_removed_handler<F>(cb: F) -> Result<()>
+where
+    F: Fn(&ServerInfo) + Send + Sync + 'static,
+{
+    *SERVER_REMOVED_CB
+        .lock()
+        .map_err(|_| ReturnCode::Unexpected)? = Some(Arc::new(cb));
+    install_server_handlers()
+}
+
+// ----- Address lookup -----
+
+/// The maximum number of addresses returned by `server_addresses()`.
+const MAX_ADDRESSES: usize = 32;
+
+/// The address family used to resolve or publish a server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressFamily {
+    /// Any address family
+    Unspecified,
+    /// IPv4
+    Ipv4,
+    /// IPv6
+    Ipv6,
+}
+
+impl AddressFamily {
+    // The value of the platform's AF_* constant for the family.
+    #[cfg(unix)]
+    fn as_raw(self) -> c_int {
+        use AddressFamily::*;
+        match self {
+            Unspecified => libc::AF_UNSPEC,
+            Ipv4 => libc::AF_INET,
+            Ipv6 => libc::AF_INET6,
+        }
+    }
+
+    // The value of the Winsock AF_* constant for the family.
+    #[cfg(windows)]
+    fn as_raw(self) -> c_int {
+        use AddressFamily::*;
+        match self {
+            Unspecified => 0,
+            Ipv4 => 2,
+            Ipv6 => 23,
+        }
+    }
+}
+
+/// Resolves the addresses of a host, in the specified address family.
+pub fn server_addresses(hostname: &str, family: AddressFamily) -> Result<Vec<String>> {
+    let hostname = CString::new(hostname).map_err(|_| ReturnCode::InvalidArg)?;
+    let mut list: [*mut c_char; MAX_ADDRESSES] = [ptr::null_mut(); MAX_ADDRESSES];
+    let mut count = MAX_ADDRESSES as u32;
+    ReturnCode::result(unsafe {
+        ffi::PhidgetNet_getServerAddressList(
+            hostname.as_ptr(),
+            family.as_raw(),
+            list.as_mut_ptr(),
+            &mut count,
+        )
+    })?;
+
+    let addrs = list
+        .iter()
+        .take(count as usize)
+        .filter(|addr| !addr.is_null())
+        .map(|&addr| unsafe { CStr::from_ptr(addr) }.to_string_lossy().into())
+        .collect();
+
+    unsafe { ffi::PhidgetNet_freeServerAddressList(list.as_mut_ptr(), count) };
+    Ok(addrs)
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// A Phidget network server, running inside the application.
+///
+/// This publishes the Phidgets that are attached to the local machine so
+/// that they can be opened by remote clients. The server is stopped when
+/// the object is dropped.
+pub struct NetServer {
+    // Handle to the server in the phidget22 library
+    server: PhidgetServerHandle,
+}
+
+impl NetServer {
+    /// Starts a network server.
+    ///
+    /// The server listens on the specified `address` and `port`, and
+    /// publishes itself with the `server_name`. If the `password` is not
+    /// empty, clients are required to supply it to connect.
+    pub fn start(
+        flags: ServerFlags,
+        family: AddressFamily,
+        server_name: &str,
+        address: &str,
+        port: i32,
+        password: &str,
+    ) -> Result<Self> {
+        let server_name = CString::new(server_name).map_err(|_| ReturnCode::InvalidArg)?;
+        let address = CString::new(address).map_err(|_| ReturnCode::InvalidArg)?;
+        let password = CString::new(password).map_err(|_| ReturnCode::InvalidArg)?;
+        let mut server: PhidgetServerHandle = ptr::null_mut();
+        ReturnCode::result(unsafe {
+            ffi::PhidgetNet_startServer(
+                flags.as_raw(),
+                family.as_raw(),
+                server_name.as_ptr(),
+                address.as_ptr(),
+                port as c_int,
+                password.as_ptr(),
+                &mut server,
+            )
+        })?;
+        Ok(Self { server })
+    }
+
+    /// Get a reference to the underlying server handle
+    pub fn as_handle(&self) -> &PhidgetServerHandle {
+        &self.server
+    }
+
+    /// Gets the info for the server, or `None` if it is not running.
+    pub fn info(&self) -> Option<ServerInfo> {
+        unsafe { ServerInfo::from_handle(self.server) }
+    }
+
+    /// Determines if the server is running.
+    pub fn is_running(&self) -> bool {
+        !self.server.is_null()
+    }
+
+    /// Stops the server.
+    ///
+    /// This is done automatically when the server is dropped.
+    pub fn stop(&mut self) -> Result<()> {
+        if self.server.is_null() {
+            return Ok(());
+        }
+        ReturnCode::result(unsafe { ffi::PhidgetNet_stopServer(&mut self.server) })?;
+        self.server = ptr::null_mut();
+        Ok(())
+    }
+}
+
+unsafe impl Send for NetServer {}
+
+impl Drop for NetServer {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_type_try_from() {
+        use ServerType::*;
+        let types = [
+            None,
+            DeviceListener,
+            Device,
+            DeviceRemote,
+            WwwListener,
+            Www,
+            WwwRemote,
+            Sbc,
+        ];
+        for stype in types {
+            assert_eq!(ServerType::try_from(stype as u32), Ok(stype));
+        }
+        assert_eq!(ServerType::try_from(8), Err(ReturnCode::InvalidArg));
+    }
+
+    #[test]
+    fn server_info_from_handle() {
+        let name = CString::new("Office").unwrap();
+        let stype = CString::new("_phidget22server._tcp").unwrap();
+        let addr = CString::new("192.168.1.10").unwrap();
+        let mut server = ffi::PhidgetServer {
+            name: name.as_ptr(),
+            stype: stype.as_ptr(),
+            type_: ffi::PhidgetServerType_PHIDGETSERVER_DEVICEREMOTE,
+            flags: ffi::PHIDGETSERVER_AUTHREQUIRED as c_int,
+            addr: addr.as_ptr(),
+            host: ptr::null(),
+            port: 5661,
+            handle: ptr::null(),
+        };
+
+        let info = unsafe { ServerInfo::from_handle(&mut server) }.unwrap();
+        assert_eq!(info.name, "Office");
+        assert_eq!(info.stype, "_phidget22server._tcp");
+        assert_eq!(info.server_type, ServerType::DeviceRemote);
+        assert_eq!(info.host, "");
+        assert_eq!(info.port, 5661);
+        assert_eq!(info.address, "192.168.1.10");
+        assert_eq!(info.flags, ServerFlags::AUTH_REQUIRED);
+        assert!(info.password_protected);
+
+        // An unknown type doesn't lose the server
+        server.type_ = 99;
+        server.flags = 0;
+        let info = unsafe { ServerInfo::from_handle(&mut server) }.unwrap();
+        assert_eq!(info.server_type, ServerType::None);
+        assert!(!info.password_protected);
+
+        assert_eq!(unsafe { ServerInfo::from_handle(ptr::null_mut()) }, None);
+    }
+
+    #[test]
+    fn server_list_bookkeeping() {
+        let info = |name: &str, port| ServerInfo {
+            name: name.into(),
+            stype: String::new(),
+            server_type: ServerType::Device,
+            host: String::new(),
+            port,
+            address: String::new(),
+            flags: ServerFlags::NONE,
+            password_protected: false,
+        };
+
+        let mut servers = Vec::new();
+        insert_server(&mut servers, info("a", 1));
+        insert_server(&mut servers, info("b", 2));
+        assert_eq!(servers, vec![info("a", 1), info("b", 2)]);
+
+        // Re-adding a server replaces the old entry
+        insert_server(&mut servers, info("a", 3));
+        assert_eq!(servers, vec![info("b", 2), info("a", 3)]);
+
+        remove_server_named(&mut servers, "b");
+        assert_eq!(servers, vec![info("a", 3)]);
+
+        // Removing an unknown server is not an error
+        remove_server_named(&mut servers, "c");
+        assert_eq!(servers, vec![info("a", 3)]);
+    }
+
+    #[test]
+    fn server_flags() {
+        let mut flags = ServerFlags::NONE;
+        assert!(flags.is_empty());
+        assert!(!flags.contains(ServerFlags::AUTH_REQUIRED));
+
+        flags |= ServerFlags::AUTH_REQUIRED;
+        assert!(flags.contains(ServerFlags::AUTH_REQUIRED));
+        assert_eq!(flags.bits(), ffi::PHIDGETSERVER_AUTHREQUIRED);
+        assert_eq!(ServerFlags::from_bits(flags.bits()), flags);
+        assert_eq!(flags & ServerFlags::from_bits(2), ServerFlags::NONE);
+    }
+}
@@ -6,21 +6,72 @@
 // to those terms.
 //
 
-use crate::{ChannelClass, DeviceClass, Result, ReturnCode};
+use crate::{ChannelClass, ChannelSubclass, DeviceClass, ErrorEventCode, Result, ReturnCode};
 use phidget_sys::{self as ffi, PhidgetHandle};
 use std::{
-    os::raw::{c_int, c_void},
+    ffi::{CStr, CString},
+    fmt,
+    os::raw::{c_char, c_int, c_uint, c_void},
+    ptr,
     time::Duration,
 };
 
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+    time::Instant,
+};
+
 /// The signature for device attach callbacks
 pub type AttachCallback = dyn Fn(&GenericPhidget) + Send + 'static;
 
 /// The signature for device detach callbacks
 pub type DetachCallback = dyn Fn(&GenericPhidget) + Send + 'static;
 
+/// The signature for device error callbacks
+pub type ErrorCallback = dyn Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static;
+
+/// The signature for device property change callbacks
+pub type PropertyChangeCallback = dyn Fn(&GenericPhidget, Property) + Send + 'static;
+
+// The waker of a task waiting for a channel to attach
+#[cfg(feature = "async")]
+type AttachWaiter = Arc<Mutex<Option<Waker>>>;
+
+// The tasks waiting for channels to attach, by channel handle
+#[cfg(feature = "async")]
+static ATTACH_WAITERS: Mutex<Vec<(usize, AttachWaiter)>> = Mutex::new(Vec::new());
+
+// Wakes the tasks waiting for the channel to attach.
+#[cfg(feature = "async")]
+fn wake_attach_waiters(phid: PhidgetHandle) {
+    // Clone the wakers out so that none are run under the lock
+    let wakers: Vec<Waker> = match ATTACH_WAITERS.lock() {
+        Ok(waiters) => waiters
+            .iter()
+            .filter(|(h, _)| *h == phid as usize)
+            .filter_map(|(_, waiter)| waiter.lock().ok().and_then(|w| w.clone()))
+            .collect(),
+        Err(_) => return,
+    };
+    for waker in wakers {
+        waker.wake();
+    }
+}
+
 // Low-level, unsafe callback for device attach events
 unsafe extern "C" fn on_attach(phid: PhidgetHandle, ctx: *mut c_void) {
+    #[cfg(feature = "async")]
+    wake_attach_waiters(phid);
+
     if !ctx.is_null() {
         let cb: &mut Box<AttachCallback> = &mut *(ctx as *mut _);
         let ph = GenericPhidget::from(phid);
@@ -37,6 +88,40 @@ unsafe extern "C" fn on_detach(phid: PhidgetHandle, ctx: *mut c_void) {
     }
 }
 
+// Low-level, unsafe callback for device error events
+unsafe extern "C" fn on_error(
+    phid: PhidgetHandle,
+    ctx: *mut c_void,
+    code: c_uint,
+    descr: *const c_char,
+) {
+    if !ctx.is_null() {
+        let cb: &mut Box<ErrorCallback> = &mut *(ctx as *mut _);
+        let ph = GenericPhidget::from(phid);
+        let descr = if descr.is_null() {
+            "".into()
+        }
+        else {
+            CStr::from_ptr(descr).to_string_lossy()
+        };
+        cb(&ph, ErrorEventCode::from(code), &descr);
+    }
+}
+
+// Low-level, unsafe callback for device property change events
+unsafe extern "C" fn on_property_change(
+    phid: PhidgetHandle,
+    ctx: *mut c_void,
+    property: *const c_char,
+) {
+    if !ctx.is_null() && !property.is_null() {
+        let cb: &mut Box<PropertyChangeCallback> = &mut *(ctx as *mut _);
+        let ph = GenericPhidget::from(phid);
+        let property = Property::from(&*CStr::from_ptr(property).to_string_lossy());
+        cb(&ph, property);
+    }
+}
+
 // ----- Callbacks -----
 
 /// Assigns a handler that will be called when the Attach event occurs for
@@ -73,6 +158,335 @@ where
     Ok(ctx)
 }
 
+/// Assigns a handler that will be called when an Error event occurs for
+/// a matching Phidget.
+pub fn set_on_error_handler<P, F>(ph: &mut P, cb: F) -> Result<*mut c_void>
+where
+    P: Phidget,
+    F: Fn(&GenericPhidget, ErrorEventCode, &str) + Send + 'static,
+{
+    // 1st box is fat ptr, 2nd is regular pointer.
+    let cb: Box<Box<ErrorCallback>> = Box::new(Box::new(cb));
+    let ctx = Box::into_raw(cb) as *mut c_void;
+
+    ReturnCode::result(unsafe {
+        ffi::Phidget_setOnErrorHandler(ph.as_handle(), Some(on_error), ctx)
+    })?;
+    Ok(ctx)
+}
+
+/// Assigns a handler that will be called when a property of a matching
+/// Phidget is changed, either by another client or by the device itself.
+/// The handler receives the property that changed, which is
+/// `Property::Other` for names not known to this crate.
+pub fn set_on_property_change_handler<P, F>(ph: &mut P, cb: F) -> Result<*mut c_void>
+where
+    P: Phidget,
+    F: Fn(&GenericPhidget, Property) + Send + 'static,
+{
+    // 1st box is fat ptr, 2nd is regular pointer.
+    let cb: Box<Box<PropertyChangeCallback>> = Box::new(Box::new(cb));
+    let ctx = Box::into_raw(cb) as *mut c_void;
+
+    ReturnCode::result(unsafe {
+        ffi::Phidget_setOnPropertyChangeHandler(ph.as_handle(), Some(on_property_change), ctx)
+    })?;
+    Ok(ctx)
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// The names of the channel properties reported by property change events.
+///
+/// These cover the properties of the device classes supported by this
+/// crate. Any other name is kept as `Property::Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Property {
+    Acceleration,
+    AccelerationChangeTrigger,
+    Algorithm,
+    AlgorithmMagnetometerGain,
+    AntennaEnabled,
+    AutoFlush,
+    BackEmfSensingState,
+    Backlight,
+    BridgeEnabled,
+    BridgeGain,
+    Contrast,
+    ControlMode,
+    CorrectionTemperature,
+    CurrentChangeTrigger,
+    CurrentLimit,
+    CurrentRegulatorGain,
+    CursorBlink,
+    CursorOn,
+    DataInterval,
+    DataRate,
+    DeadBand,
+    DistanceChangeTrigger,
+    DutyCycle,
+    Enabled,
+    Engaged,
+    FanMode,
+    FilterType,
+    FontSize,
+    FrameBuffer,
+    Frequency,
+    FrequencyCutoff,
+    HeatingEnabled,
+    HoldingCurrentLimit,
+    HumidityChangeTrigger,
+    IlluminanceChangeTrigger,
+    InputMode,
+    IoMode,
+    Kd,
+    Ki,
+    Kp,
+    LedCurrentLimit,
+    LedForwardVoltage,
+    MagneticFieldChangeTrigger,
+    MaxPosition,
+    MaxPulseWidth,
+    MinPosition,
+    MinPulseWidth,
+    OverVoltage,
+    PhChangeTrigger,
+    PortAutoSetSpeed,
+    PortMode,
+    PortPower,
+    Position,
+    PositionChangeTrigger,
+    PowerEnabled,
+    PowerSupply,
+    PressureChangeTrigger,
+    RescaleFactor,
+    ResistanceChangeTrigger,
+    RtdType,
+    RtdWireSetup,
+    ScreenSize,
+    Sensitivity,
+    SensorType,
+    SensorValueChangeTrigger,
+    Sleeping,
+    SonarQuietMode,
+    SpeedRampingState,
+    SplChangeTrigger,
+    SplRange,
+    StallVelocity,
+    State,
+    TargetBrakingStrength,
+    TargetPosition,
+    TargetVelocity,
+    TemperatureChangeTrigger,
+    ThermocoupleType,
+    Torque,
+    TouchValueChangeTrigger,
+    VelocityLimit,
+    Voltage,
+    VoltageChangeTrigger,
+    VoltageOutputRange,
+    VoltageRange,
+    VoltageRatioChangeTrigger,
+    /// A property that is not otherwise known to this crate
+    Other(String),
+}
+
+impl Property {
+    /// Gets the phidget22 name of the property.
+    pub fn as_str(&self) -> &str {
+        use Property::*;
+        match self {
+            Acceleration => "Acceleration",
+            AccelerationChangeTrigger => "AccelerationChangeTrigger",
+            Algorithm => "Algorithm",
+            AlgorithmMagnetometerGain => "AlgorithmMagnetometerGain",
+            AntennaEnabled => "AntennaEnabled",
+            AutoFlush => "AutoFlush",
+            BackEmfSensingState => "BackEMFSensingState",
+            Backlight => "Backlight",
+            BridgeEnabled => "BridgeEnabled",
+            BridgeGain => "BridgeGain",
+            Contrast => "Contrast",
+            ControlMode => "ControlMode",
+            CorrectionTemperature => "CorrectionTemperature",
+            CurrentChangeTrigger => "CurrentChangeTrigger",
+            CurrentLimit => "CurrentLimit",
+            CurrentRegulatorGain => "CurrentRegulatorGain",
+            CursorBlink => "CursorBlink",
+            CursorOn => "CursorOn",
+            DataInterval => "DataInterval",
+            DataRate => "DataRate",
+            DeadBand => "DeadBand",
+            DistanceChangeTrigger => "DistanceChangeTrigger",
+            DutyCycle => "DutyCycle",
+            Enabled => "Enabled",
+            Engaged => "Engaged",
+            FanMode => "FanMode",
+            FilterType => "FilterType",
+            FontSize => "FontSize",
+            FrameBuffer => "FrameBuffer",
+            Frequency => "Frequency",
+            FrequencyCutoff => "FrequencyCutoff",
+            HeatingEnabled => "HeatingEnabled",
+            HoldingCurrentLimit => "HoldingCurrentLimit",
+            HumidityChangeTrigger => "HumidityChangeTrigger",
+            IlluminanceChangeTrigger => "IlluminanceChangeTrigger",
+            InputMode => "InputMode",
+            IoMode => "IOMode",
+            Kd => "Kd",
+            Ki => "Ki",
+            Kp => "Kp",
+            LedCurrentLimit => "LEDCurrentLimit",
+            LedForwardVoltage => "LEDForwardVoltage",
+            MagneticFieldChangeTrigger => "MagneticFieldChangeTrigger",
+            MaxPosition => "MaxPosition",
+            MaxPulseWidth => "MaxPulseWidth",
+            MinPosition => "MinPosition",
+            MinPulseWidth => "MinPulseWidth",
+            OverVoltage => "OverVoltage",
+            PhChangeTrigger => "PHChangeTrigger",
+            PortAutoSetSpeed => "PortAutoSetSpeed",
+            PortMode => "PortMode",
+            PortPower => "PortPower",
+            Position => "Position",
+            PositionChangeTrigger => "PositionChangeTrigger",
+            PowerEnabled => "PowerEnabled",
+            PowerSupply => "PowerSupply",
+            PressureChangeTrigger => "PressureChangeTrigger",
+            RescaleFactor => "RescaleFactor",
+            ResistanceChangeTrigger => "ResistanceChangeTrigger",
+            RtdType => "RTDType",
+            RtdWireSetup => "RTDWireSetup",
+            ScreenSize => "ScreenSize",
+            Sensitivity => "Sensitivity",
+            SensorType => "SensorType",
+            SensorValueChangeTrigger => "SensorValueChangeTrigger",
+            Sleeping => "Sleeping",
+            SonarQuietMode => "SonarQuietMode",
+            SpeedRampingState => "SpeedRampingState",
+            SplChangeTrigger => "SPLChangeTrigger",
+            SplRange => "SPLRange",
+            StallVelocity => "StallVelocity",
+            State => "State",
+            TargetBrakingStrength => "TargetBrakingStrength",
+            TargetPosition => "TargetPosition",
+            TargetVelocity => "TargetVelocity",
+            TemperatureChangeTrigger => "TemperatureChangeTrigger",
+            ThermocoupleType => "ThermocoupleType",
+            Torque => "Torque",
+            TouchValueChangeTrigger => "TouchValueChangeTrigger",
+            VelocityLimit => "VelocityLimit",
+            Voltage => "Voltage",
+            VoltageChangeTrigger => "VoltageChangeTrigger",
+            VoltageOutputRange => "VoltageOutputRange",
+            VoltageRange => "VoltageRange",
+            VoltageRatioChangeTrigger => "VoltageRatioChangeTrigger",
+            Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for Property {
+    fn from(name: &str) -> Self {
+        use Property::*;
+        match name {
+            "Acceleration" => Acceleration,
+            "AccelerationChangeTrigger" => AccelerationChangeTrigger,
+            "Algorithm" => Algorithm,
+            "AlgorithmMagnetometerGain" => AlgorithmMagnetometerGain,
+            "AntennaEnabled" => AntennaEnabled,
+            "AutoFlush" => AutoFlush,
+            "BackEMFSensingState" => BackEmfSensingState,
+            "Backlight" => Backlight,
+            "BridgeEnabled" => BridgeEnabled,
+            "BridgeGain" => BridgeGain,
+            "Contrast" => Contrast,
+            "ControlMode" => ControlMode,
+            "CorrectionTemperature" => CorrectionTemperature,
+            "CurrentChangeTrigger" => CurrentChangeTrigger,
+            "CurrentLimit" => CurrentLimit,
+            "CurrentRegulatorGain" => CurrentRegulatorGain,
+            "CursorBlink" => CursorBlink,
+            "CursorOn" => CursorOn,
+            "DataInterval" => DataInterval,
+            "DataRate" => DataRate,
+            "DeadBand" => DeadBand,
+            "DistanceChangeTrigger" => DistanceChangeTrigger,
+            "DutyCycle" => DutyCycle,
+            "Enabled" => Enabled,
+            "Engaged" => Engaged,
+            "FanMode" => FanMode,
+            "FilterType" => FilterType,
+            "FontSize" => FontSize,
+            "FrameBuffer" => FrameBuffer,
+            "Frequency" => Frequency,
+            "FrequencyCutoff" => FrequencyCutoff,
+            "HeatingEnabled" => HeatingEnabled,
+            "HoldingCurrentLimit" => HoldingCurrentLimit,
+            "HumidityChangeTrigger" => HumidityChangeTrigger,
+            "IlluminanceChangeTrigger" => IlluminanceChangeTrigger,
+            "InputMode" => InputMode,
+            "IOMode" => IoMode,
+            "Kd" => Kd,
+            "Ki" => Ki,
+            "Kp" => Kp,
+            "LEDCurrentLimit" => LedCurrentLimit,
+            "LEDForwardVoltage" => LedForwardVoltage,
+            "MagneticFieldChangeTrigger" => MagneticFieldChangeTrigger,
+            "MaxPosition" => MaxPosition,
+            "MaxPulseWidth" => MaxPulseWidth,
+            "MinPosition" => MinPosition,
+            "MinPulseWidth" => MinPulseWidth,
+            "OverVoltage" => OverVoltage,
+            "PHChangeTrigger" => PhChangeTrigger,
+            "PortAutoSetSpeed" => PortAutoSetSpeed,
+            "PortMode" => PortMode,
+            "PortPower" => PortPower,
+            "Position" => Position,
+            "PositionChangeTrigger" => PositionChangeTrigger,
+            "PowerEnabled" => PowerEnabled,
+            "PowerSupply" => PowerSupply,
+            "PressureChangeTrigger" => PressureChangeTrigger,
+            "RescaleFactor" => RescaleFactor,
+            "ResistanceChangeTrigger" => ResistanceChangeTrigger,
+            "RTDType" => RtdType,
+            "RTDWireSetup" => RtdWireSetup,
+            "ScreenSize" => ScreenSize,
+            "Sensitivity" => Sensitivity,
+            "SensorType" => SensorType,
+            "SensorValueChangeTrigger" => SensorValueChangeTrigger,
+            "Sleeping" => Sleeping,
+            "SonarQuietMode" => SonarQuietMode,
+            "SpeedRampingState" => SpeedRampingState,
+            "SPLChangeTrigger" => SplChangeTrigger,
+            "SPLRange" => SplRange,
+            "StallVelocity" => StallVelocity,
+            "State" => State,
+            "TargetBrakingStrength" => TargetBrakingStrength,
+            "TargetPosition" => TargetPosition,
+            "TargetVelocity" => TargetVelocity,
+            "TemperatureChangeTrigger" => TemperatureChangeTrigger,
+            "ThermocoupleType" => ThermocoupleType,
+            "Torque" => Torque,
+            "TouchValueChangeTrigger" => TouchValueChangeTrigger,
+            "VelocityLimit" => VelocityLimit,
+            "Voltage" => Voltage,
+            "VoltageChangeTrigger" => VoltageChangeTrigger,
+            "VoltageOutputRange" => VoltageOutputRange,
+            "VoltageRange" => VoltageRange,
+            "VoltageRatioChangeTrigger" => VoltageRatioChangeTrigger,
+            _ => Other(name.into()),
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////
 
 /// The base trait and implementation for Phidgets
@@ -98,6 +512,32 @@ pub trait Phidget: Send {
         self.open_wait(crate::TIMEOUT_DEFAULT)
     }
 
+    /// Attempt to open the channel, returning a future that completes
+    /// when the channel is attached, or fails after the default timeout.
+    #[cfg(feature = "async")]
+    fn open_attached(&mut self) -> OpenAttached<'_> {
+        self.open_attached_timeout(crate::TIMEOUT_DEFAULT)
+    }
+
+    /// Attempt to open the channel, returning a future that completes
+    /// when the channel is attached, or fails after the specified timeout.
+    /// A timeout of `TIMEOUT_INFINITE` waits forever.
+    #[cfg(feature = "async")]
+    fn open_attached_timeout(&mut self, to: Duration) -> OpenAttached<'_> {
+        let attach_ctx = self.attach_handler_ctx();
+        OpenAttached::new(self.as_handle(), to, attach_ctx)
+    }
+
+    /// Gets the context of the attach handler that was set on the channel,
+    /// if any.
+    ///
+    /// This lets `open_attached()` wait on the channel's attach event
+    /// without replacing the application's handler.
+    #[doc(hidden)]
+    fn attach_handler_ctx(&self) -> Option<*mut c_void> {
+        None
+    }
+
     /// Closes the channel
     fn close(&mut self) -> Result<()> {
         ReturnCode::result(unsafe { ffi::Phidget_close(self.as_handle()) })
@@ -235,6 +675,117 @@ pub trait Phidget: Send {
         crate::get_ffi_string(|s| unsafe { ffi::Phidget_getDeviceClassName(self.as_handle(), s) })
     }
 
+    /// Get the name of the device
+    fn device_name(&mut self) -> Result<String> {
+        crate::get_ffi_string(|s| unsafe { ffi::Phidget_getDeviceName(self.as_handle(), s) })
+    }
+
+    /// Get the SKU (part number) of the device
+    fn device_sku(&mut self) -> Result<String> {
+        crate::get_ffi_string(|s| unsafe { ffi::Phidget_getDeviceSKU(self.as_handle(), s) })
+    }
+
+    /// Get the version of the device
+    fn device_version(&mut self) -> Result<i32> {
+        let mut ver: c_int = 0;
+        ReturnCode::result(unsafe { ffi::Phidget_getDeviceVersion(self.as_handle(), &mut ver) })?;
+        Ok(ver as i32)
+    }
+
+    /// Get the ID of the device.
+    /// This is one of the `ffi::Phidget_DeviceID_PHIDID_*` values.
+    fn device_id(&mut self) -> Result<ffi::Phidget_DeviceID> {
+        let mut id: ffi::Phidget_DeviceID = ffi::Phidget_DeviceID_PHIDID_NOTHING;
+        ReturnCode::result(unsafe { ffi::Phidget_getDeviceID(self.as_handle(), &mut id) })?;
+        Ok(id)
+    }
+
+    /// Get the label of the device
+    fn device_label(&mut self) -> Result<String> {
+        crate::get_ffi_string(|s| unsafe { ffi::Phidget_getDeviceLabel(self.as_handle(), s) })
+    }
+
+    /// Sets the label of the device to be opened.
+    /// This is used as a filter to select the device by its label.
+    /// This must be set before the channel is opened.
+    fn set_device_label(&mut self, label: &str) -> Result<()> {
+        let label = CString::new(label).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe { ffi::Phidget_setDeviceLabel(self.as_handle(), label.as_ptr()) })
+    }
+
+    /// Writes a label to the flash memory of the device, where it will
+    /// persist across power cycles.
+    /// The channel must be open to write the label.
+    fn write_device_label(&mut self, label: &str) -> Result<()> {
+        let label = CString::new(label).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe {
+            ffi::Phidget_writeDeviceLabel(self.as_handle(), label.as_ptr())
+        })
+    }
+
+    /// Gets the subclass of the channel
+    fn channel_subclass(&mut self) -> Result<ChannelSubclass> {
+        let mut cls = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_NONE;
+        ReturnCode::result(unsafe { ffi::Phidget_getChannelSubclass(self.as_handle(), &mut cls) })?;
+        ChannelSubclass::try_from(cls)
+    }
+
+    /// Gets the parent of the channel.
+    /// For a channel, this is the device it's on. For a VINT device, it's
+    /// the hub port it's attached to.
+    fn parent(&mut self) -> Result<GenericPhidget> {
+        let mut phid: PhidgetHandle = ptr::null_mut();
+        ReturnCode::result(unsafe { ffi::Phidget_getParent(self.as_handle(), &mut phid) })?;
+        if phid.is_null() {
+            return Err(ReturnCode::NoEnt);
+        }
+        Ok(GenericPhidget::from(phid))
+    }
+
+    /// Gets the hub that the channel is attached to.
+    fn hub(&mut self) -> Result<GenericPhidget> {
+        let mut phid: PhidgetHandle = ptr::null_mut();
+        ReturnCode::result(unsafe { ffi::Phidget_getHub(self.as_handle(), &mut phid) })?;
+        if phid.is_null() {
+            return Err(ReturnCode::NoEnt);
+        }
+        Ok(GenericPhidget::from(phid))
+    }
+
+    /// Gets the number of ports on the VINT Hub to which the channel is
+    /// attached.
+    fn hub_port_count(&mut self) -> Result<i32> {
+        let mut n: c_int = 0;
+        ReturnCode::result(unsafe { ffi::Phidget_getHubPortCount(self.as_handle(), &mut n) })?;
+        Ok(n as i32)
+    }
+
+    /// Gets the name of the server that the channel is being accessed
+    /// through, when it is opened remotely.
+    fn server_name(&mut self) -> Result<String> {
+        crate::get_ffi_string(|s| unsafe { ffi::Phidget_getServerName(self.as_handle(), s) })
+    }
+
+    /// Sets the name of the server to open the channel on.
+    /// This must be set before the channel is opened.
+    fn set_server_name(&mut self, name: &str) -> Result<()> {
+        let name = CString::new(name).map_err(|_| ReturnCode::InvalidArg)?;
+        ReturnCode::result(unsafe { ffi::Phidget_setServerName(self.as_handle(), name.as_ptr()) })
+    }
+
+    /// Gets the hostname of the server that the channel is being accessed
+    /// through, when it is opened remotely.
+    fn server_hostname(&mut self) -> Result<String> {
+        crate::get_ffi_string(|s| unsafe { ffi::Phidget_getServerHostname(self.as_handle(), s) })
+    }
+
+    /// Gets the full identity and metadata of the channel and its device
+    /// in a single call.
+    /// The channel must be open and attached.
+    fn device_info(&mut self) -> Result<DeviceInfo> {
+        DeviceInfo::new(self)
+    }
+
     // ----- Filters -----
 
     /// Determines whether this channel is a VINT Hub port channel, or part
@@ -305,6 +856,275 @@ pub trait Phidget: Send {
 
 /////////////////////////////////////////////////////////////////////////////
 
+/// A future that opens a channel and completes when it is attached.
+///
+/// This is returned from `Phidget::open_attached()`. The channel is opened
+/// the first time the future is polled, and the task is woken from the
+/// channel's attach event. If the timeout expires before the channel
+/// attaches, the channel is closed again and the future resolves to a
+/// `ReturnCode::Timeout` error. If the future is dropped before it
+/// completes, the channel is left open, as with `Phidget::open()`.
+///
+/// The future keeps an attach handler set through the device type, but
+/// one set on the channel with the free `set_on_attach_handler()` function
+/// is replaced.
+#[cfg(feature = "async")]
+pub struct OpenAttached<'a> {
+    // Handle to the channel being opened
+    phid: PhidgetHandle,
+    // The time to wait for the attachment (zero waits forever)
+    timeout: Duration,
+    // The context of the application's attach handler, if any
+    attach_ctx: Option<*mut c_void>,
+    // The time at which the channel was opened
+    start: Option<Instant>,
+    // The waker for the attach handler, once the channel is opened
+    waiter: Option<AttachWaiter>,
+    // Wakes the task when the timeout expires
+    deadline: Option<Deadline>,
+    // The channel is borrowed until the future completes
+    _marker: PhantomData<&'a mut ()>,
+}
+
+#[cfg(feature = "async")]
+impl OpenAttached<'_> {
+    fn new(phid: PhidgetHandle, timeout: Duration, attach_ctx: Option<*mut c_void>) -> Self {
+        Self {
+            phid,
+            timeout,
+            attach_ctx,
+            start: None,
+            waiter: None,
+            deadline: None,
+            _marker: PhantomData,
+        }
+    }
+
+    // Registers the task to be woken by the attach event, then opens the
+    // channel.
+    fn begin(&mut self, waker: &Waker) -> Result<Instant> {
+        // An application handler was set through `on_attach`, which already
+        // wakes the waiters, so it must be left in place.
+        if self.attach_ctx.is_none() {
+            ReturnCode::result(unsafe {
+                ffi::Phidget_setOnAttachHandler(self.phid, Some(on_attach), ptr::null_mut())
+            })?;
+        }
+
+        let waiter = Arc::new(Mutex::new(Some(waker.clone())));
+        if let Ok(mut waiters) = ATTACH_WAITERS.lock() {
+            waiters.push((self.phid as usize, Arc::clone(&waiter)));
+        }
+        self.waiter = Some(Arc::clone(&waiter));
+
+        ReturnCode::result(unsafe { ffi::Phidget_open(self.phid) })?;
+
+        let start = Instant::now();
+        if !self.timeout.is_zero() {
+            self.deadline = Some(Deadline::new(start + self.timeout, waiter));
+        }
+        Ok(start)
+    }
+
+    // Stops waiting for the attach event.
+    fn finish(&mut self) {
+        self.deadline = None;
+        if let Some(waiter) = self.waiter.take() {
+            if let Ok(mut waiters) = ATTACH_WAITERS.lock() {
+                waiters.retain(|(_, w)| !Arc::ptr_eq(w, &waiter));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Future for OpenAttached<'_> {
+    type Output = Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let start = match self.start {
+            Some(start) => start,
+            None => match self.begin(cx.waker()) {
+                Ok(start) => *self.start.insert(start),
+                Err(err) => {
+                    self.finish();
+                    return Poll::Ready(Err(err));
+                }
+            },
+        };
+
+        // Update the waker before checking, so an attach isn't missed
+        if let Some(Ok(mut w)) = self.waiter.as_ref().map(|w| w.lock()) {
+            if !w.as_ref().is_some_and(|w| w.will_wake(cx.waker())) {
+                *w = Some(cx.waker().clone());
+            }
+        }
+
+        let mut attached: c_int = 0;
+        if let Err(err) =
+            ReturnCode::result(unsafe { ffi::Phidget_getAttached(self.phid, &mut attached) })
+        {
+            self.finish();
+            return Poll::Ready(Err(err));
+        }
+        if attached != 0 {
+            self.finish();
+            return Poll::Ready(Ok(()));
+        }
+
+        if !self.timeout.is_zero() && start.elapsed() >= self.timeout {
+            self.finish();
+            unsafe {
+                ffi::Phidget_close(self.phid);
+            }
+            return Poll::Ready(Err(ReturnCode::Timeout));
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for OpenAttached<'_> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(feature = "async")]
+unsafe impl Send for OpenAttached<'_> {}
+
+// A background thread that wakes a task once, at a deadline, unless it is
+// dropped first.
+#[cfg(feature = "async")]
+struct Deadline {
+    done: Arc<AtomicBool>,
+    thread: thread::Thread,
+}
+
+#[cfg(feature = "async")]
+impl Deadline {
+    fn new(at: Instant, waiter: AttachWaiter) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        let thr_done = Arc::clone(&done);
+        let thread = thread::spawn(move || {
+            while !thr_done.load(Ordering::Acquire) {
+                let now = Instant::now();
+                if now >= at {
+                    let waker = waiter.lock().ok().and_then(|w| w.clone());
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                    break;
+                }
+                thread::park_timeout(at - now);
+            }
+        })
+        .thread()
+        .clone();
+        Self { done, thread }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// The full identity and metadata of a channel and its device.
+///
+/// This is read from an attached channel with `Phidget::device_info()`.
+/// Properties that don't apply to the channel, such as the server name
+/// of a local channel, are `None`. So are classes reported by a newer
+/// phidget22 library that this crate doesn't know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The serial number of the device (or the VINT Hub it's attached to)
+    pub serial_number: i32,
+    /// The SKU (part number) of the device
+    pub device_sku: String,
+    /// The name of the device
+    pub device_name: String,
+    /// The version of the device
+    pub device_version: i32,
+    /// The ID of the device
+    pub device_id: ffi::Phidget_DeviceID,
+    /// The class of the device, if it is known to this crate
+    pub device_class: Option<DeviceClass>,
+    /// The label of the device, if it has one
+    pub device_label: Option<String>,
+    /// The class of the channel, if it is known to this crate
+    pub channel_class: Option<ChannelClass>,
+    /// The subclass of the channel, if it is known to this crate
+    pub channel_subclass: Option<ChannelSubclass>,
+    /// The name of the channel
+    pub channel_name: String,
+    /// The channel index on the device
+    pub channel: i32,
+    /// The VINT Hub port to which the device is attached, if any
+    pub hub_port: Option<i32>,
+    /// The number of ports on the VINT Hub, if any
+    pub hub_port_count: Option<i32>,
+    /// Whether the channel is a VINT Hub port, itself
+    pub is_hub_port_device: bool,
+    /// Whether the channel is opened locally
+    pub is_local: bool,
+    /// The name of the server, for a remote channel
+    pub server_name: Option<String>,
+    /// The hostname of the server, for a remote channel
+    pub server_hostname: Option<String>,
+}
+
+impl DeviceInfo {
+    /// Reads the full identity and metadata from an attached channel.
+    pub fn new<P: Phidget + ?Sized>(ph: &mut P) -> Result<Self> {
+        let is_local = ph.is_local()?;
+        let (server_name, server_hostname) = if is_local {
+            (None, None)
+        }
+        else {
+            (ph.server_name().ok(), ph.server_hostname().ok())
+        };
+
+        // Read the raw classes, so that an unknown value isn't an error
+        let phid = ph.as_handle();
+        let mut device_class = ffi::Phidget_DeviceClass_PHIDCLASS_NOTHING;
+        let mut channel_class = ffi::Phidget_ChannelClass_PHIDCHCLASS_NOTHING;
+        let mut channel_subclass = ffi::Phidget_ChannelSubclass_PHIDCHSUBCLASS_NONE;
+        unsafe {
+            ReturnCode::result(ffi::Phidget_getDeviceClass(phid, &mut device_class))?;
+            ReturnCode::result(ffi::Phidget_getChannelClass(phid, &mut channel_class))?;
+            ReturnCode::result(ffi::Phidget_getChannelSubclass(phid, &mut channel_subclass))?;
+        }
+
+        Ok(Self {
+            serial_number: ph.serial_number()?,
+            device_sku: ph.device_sku()?,
+            device_name: ph.device_name()?,
+            device_version: ph.device_version()?,
+            device_id: ph.device_id()?,
+            device_class: DeviceClass::try_from(device_class).ok(),
+            device_label: ph.device_label().ok().filter(|s| !s.is_empty()),
+            channel_class: ChannelClass::try_from(channel_class).ok(),
+            channel_subclass: ChannelSubclass::try_from(channel_subclass).ok(),
+            channel_name: ph.channel_name()?,
+            channel: ph.channel()?,
+            hub_port: ph.hub_port().ok().filter(|&port| port >= 0),
+            hub_port_count: ph.hub_port_count().ok(),
+            is_hub_port_device: ph.is_hub_port_device()?,
+            is_local,
+            server_name,
+            server_hostname,
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
 /// A wrapper for a generic phidget.
 ///
 /// This contains a wrapper around a generic PhidgetHandle, which might be
@@ -340,3 +1160,118 @@ impl From<PhidgetHandle> for GenericPhidget {
         Self::new(phid)
     }
 }
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_names_round_trip() {
+        use Property::*;
+        let props = [
+            Acceleration,
+            AccelerationChangeTrigger,
+            Algorithm,
+            AlgorithmMagnetometerGain,
+            AntennaEnabled,
+            AutoFlush,
+            BackEmfSensingState,
+            Backlight,
+            BridgeEnabled,
+            BridgeGain,
+            Contrast,
+            ControlMode,
+            CorrectionTemperature,
+            CurrentChangeTrigger,
+            CurrentLimit,
+            CurrentRegulatorGain,
+            CursorBlink,
+            CursorOn,
+            DataInterval,
+            DataRate,
+            DeadBand,
+            DistanceChangeTrigger,
+            DutyCycle,
+            Enabled,
+            Engaged,
+            FanMode,
+            FilterType,
+            FontSize,
+            FrameBuffer,
+            Frequency,
+            FrequencyCutoff,
+            HeatingEnabled,
+            HoldingCurrentLimit,
+            HumidityChangeTrigger,
+            IlluminanceChangeTrigger,
+            InputMode,
+            IoMode,
+            Kd,
+            Ki,
+            Kp,
+            LedCurrentLimit,
+            LedForwardVoltage,
+            MagneticFieldChangeTrigger,
+            MaxPosition,
+            MaxPulseWidth,
+            MinPosition,
+            MinPulseWidth,
+            OverVoltage,
+            PhChangeTrigger,
+            PortAutoSetSpeed,
+            PortMode,
+            PortPower,
+            Position,
+            PositionChangeTrigger,
+            PowerEnabled,
+            PowerSupply,
+            PressureChangeTrigger,
+            RescaleFactor,
+            ResistanceChangeTrigger,
+            RtdType,
+            RtdWireSetup,
+            ScreenSize,
+            Sensitivity,
+            SensorType,
+            SensorValueChangeTrigger,
+            Sleeping,
+            SonarQuietMode,
+            SpeedRampingState,
+            SplChangeTrigger,
+            SplRange,
+            StallVelocity,
+            State,
+            TargetBrakingStrength,
+            TargetPosition,
+            TargetVelocity,
+            TemperatureChangeTrigger,
+            ThermocoupleType,
+            Torque,
+            TouchValueChangeTrigger,
+            VelocityLimit,
+            Voltage,
+            VoltageChangeTrigger,
+            VoltageOutputRange,
+            VoltageRange,
+            VoltageRatioChangeTrigger,
+        ];
+        for prop in props {
+            assert_eq!(Property::from(prop.as_str()), prop);
+        }
+        assert_eq!(Property::from("LEDCurrentLimit"), LedCurrentLimit);
+        assert_eq!(Property::from("RTDType"), RtdType);
+        assert_eq!(Property::from("IOMode"), IoMode);
+        assert_eq!(Property::from("PHChangeTrigger"), PhChangeTrigger);
+        assert_eq!(Property::from("BackEMFSensingState"), BackEmfSensingState);
+    }
+
+    #[test]
+    fn unknown_property_is_other() {
+        let prop = Property::from("SomethingNew");
+        assert_eq!(prop, Property::Other("SomethingNew".into()));
+        assert_eq!(prop.as_str(), "SomethingNew");
+        assert_eq!(prop.to_string(), "SomethingNew");
+    }
+}
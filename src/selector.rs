@@ -0,0 +1,123 @@
+// phidget-rs/src/selector.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+//! Channel addressing.
+//!
+//! A [`ChannelSelector`] collects the filters used to pick out a specific
+//! channel when it is opened. A device can be addressed by its serial
+//! number, or by a label that was written to its flash memory, which
+//! follows the device if a board is swapped out.
+//!
+//! ```no_run
+//! use phidget::{ChannelSelector, Phidget, TemperatureSensor};
+//!
+//! let mut sensor = TemperatureSensor::new();
+//! ChannelSelector::label("oven-left")
+//!     .port(2)
+//!     .channel(0)
+//!     .apply(&mut sensor)?;
+//! sensor.open_wait_default()?;
+//! # Ok::<(), phidget::Error>(())
+//! ```
+//!
+
+use crate::{Phidget, Result};
+
+/// The filters to select a channel to open.
+///
+/// Any filter that is not set will match any value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ChannelSelector {
+    // The device serial number
+    serial_number: Option<i32>,
+    // The device label
+    label: Option<String>,
+    // The VINT Hub port
+    hub_port: Option<i32>,
+    // Whether to open the hub port directly
+    is_hub_port_device: Option<bool>,
+    // The channel index on the device
+    channel: Option<i32>,
+    // The name of the network server
+    server_name: Option<String>,
+}
+
+impl ChannelSelector {
+    /// Creates a selector that matches any channel.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Creates a selector for a device with the specified label.
+    pub fn label(label: &str) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a selector for a device with the specified serial number.
+    /// For a VINT device, this is the serial number of the hub.
+    pub fn serial_number(sn: i32) -> Self {
+        Self {
+            serial_number: Some(sn),
+            ..Self::default()
+        }
+    }
+
+    /// Selects the VINT Hub port to which the device is attached.
+    pub fn port(mut self, port: i32) -> Self {
+        self.hub_port = Some(port);
+        self
+    }
+
+    /// Selects whether to use a VINT Hub port directly as the channel.
+    pub fn hub_port_device(mut self, on: bool) -> Self {
+        self.is_hub_port_device = Some(on);
+        self
+    }
+
+    /// Selects the channel index on the device.
+    pub fn channel(mut self, chan: i32) -> Self {
+        self.channel = Some(chan);
+        self
+    }
+
+    /// Selects the network server through which to open the channel.
+    pub fn server(mut self, name: &str) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// Applies the filters to a Phidget.
+    /// This must be done before the channel is opened.
+    pub fn apply<P: Phidget + ?Sized>(&self, ph: &mut P) -> Result<()> {
+        if let Some(sn) = self.serial_number {
+            ph.set_serial_number(sn)?;
+        }
+        if let Some(label) = &self.label {
+            ph.set_device_label(label)?;
+        }
+        if let Some(on) = self.is_hub_port_device {
+            ph.set_is_hub_port_device(on)?;
+        }
+        if let Some(port) = self.hub_port {
+            ph.set_hub_port(port)?;
+        }
+        if let Some(chan) = self.channel {
+            ph.set_channel(chan)?;
+        }
+        if let Some(name) = &self.server_name {
+            ph.set_server_name(name)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+// phidget-rs/src/stream.rs
+//
+// Copyright (c) 2023, Frank Pagliughi
+//
+// This file is part of the 'phidget-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+//
+//! Asynchronous event streams.
+//!
+//! The device types can turn their change events into a
+//! [`Stream`](futures_core::Stream) of values, such as
+//! `VoltageInput::voltage_changes()`.
+//!
+//! The events are delivered from the phidget22 library's event thread
+//! through a bounded buffer. The library thread can't be blocked waiting
+//! for the application, so when the buffer fills up, events are discarded
+//! according to the stream's [`Overflow`](crate::stream::Overflow) policy.
+//! The number of discarded events is available from
+//! [`EventStream::dropped()`](crate::stream::EventStream::dropped), so
+//! the application can tell when it has fallen behind.
+//!
+//! The stream holds a mutable borrow of the device. When the stream is
+//! dropped, the callback is unregistered from the device, and the device
+//! can be used again.
+//!
+
+use futures_core::Stream;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+/// The default number of events that can be buffered by an event stream
+/// before events get discarded.
+pub const EVENT_BUFFER_SIZE: usize = 64;
+
+/// What an event stream does with a new event when its buffer is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Discard the new event, keeping the ones already in the buffer.
+    #[default]
+    DropNewest,
+    /// Discard the oldest event in the buffer to make room for the new one.
+    DropOldest,
+}
+
+// The state shared between the sending and receiving side of a stream.
+struct Shared<T> {
+    // The buffered events
+    queue: VecDeque<T>,
+    // The maximum number of events in the buffer
+    capacity: usize,
+    // What to do when the buffer is full
+    overflow: Overflow,
+    // The number of events that were discarded
+    dropped: u64,
+    // The waker for the task polling the stream
+    waker: Option<Waker>,
+    // Whether the sending side is gone
+    closed: bool,
+}
+
+// Locks the shared state, ignoring a poisoned mutex.
+fn lock<T>(shared: &Mutex<Shared<T>>) -> MutexGuard<'_, Shared<T>> {
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Creates the sending and receiving sides of an event stream.
+pub(crate) fn channel<T>() -> (EventSender<T>, EventReceiver<T>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        queue: VecDeque::new(),
+        capacity: EVENT_BUFFER_SIZE,
+        overflow: Overflow::default(),
+        dropped: 0,
+        waker: None,
+        closed: false,
+    }));
+    (EventSender(Arc::clone(&shared)), EventReceiver(shared))
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// The sending side of an event stream.
+///
+/// This is captured by the device callbacks to forward events into the
+/// stream without blocking the phidget22 event thread.
+pub(crate) struct EventSender<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> EventSender<T> {
+    /// Sends an event to the stream.
+    ///
+    /// If the stream's buffer is full, an event is discarded according to
+    /// the overflow policy, and counted as dropped.
+    pub(crate) fn send(&self, evt: T) {
+        let waker = {
+            let mut shared = lock(&self.0);
+            if shared.queue.len() >= shared.capacity {
+                shared.dropped += 1;
+                match shared.overflow {
+                    Overflow::DropNewest => return,
+                    Overflow::DropOldest => {
+                        let n = shared.queue.len() + 1 - shared.capacity;
+                        shared.queue.drain(..n);
+                    }
+                }
+            }
+            shared.queue.push_back(evt);
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for EventSender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut shared = lock(&self.0);
+            shared.closed = true;
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving side of an event stream.
+pub(crate) struct EventReceiver<T>(Arc<Mutex<Shared<T>>>);
+
+/////////////////////////////////////////////////////////////////////////////
+
+/// A stream of events from a device.
+///
+/// The events are buffered, up to the stream's capacity, until they are
+/// read. When the buffer is full, events are discarded according to the
+/// stream's [`Overflow`] policy, which defaults to dropping the newest
+/// events.
+///
+/// The callback that feeds the stream is unregistered from the device
+/// when the stream is dropped.
+pub struct EventStream<'a, T> {
+    // The receiving side of the event channel
+    rx: EventReceiver<T>,
+    // Unregisters the callback from the device
+    unregister: Option<Box<dyn FnOnce() + Send + 'a>>,
+}
+
+impl<'a, T> EventStream<'a, T> {
+    /// Creates a stream from the receiver, which will run the `unregister`
+    /// function when dropped.
+    pub(crate) fn new<F>(rx: EventReceiver<T>, unregister: F) -> Self
+    where
+        F: FnOnce() + Send + 'a,
+    {
+        Self {
+            rx,
+            unregister: Some(Box::new(unregister)),
+        }
+    }
+
+    /// Gets the maximum number of events that the stream buffers.
+    pub fn capacity(&self) -> usize {
+        lock(&self.rx.0).capacity
+    }
+
+    /// Sets the maximum number of events that the stream buffers.
+    ///
+    /// The capacity is at least one event. If the buffer already holds
+    /// more events than the new capacity, they are kept, but no new events
+    /// are buffered until it drains below the capacity, or the oldest
+    /// ones are discarded, depending on the [`Overflow`] policy.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        lock(&self.rx.0).capacity = capacity.max(1);
+    }
+
+    /// Gets the policy for discarding events when the buffer is full.
+    pub fn overflow(&self) -> Overflow {
+        lock(&self.rx.0).overflow
+    }
+
+    /// Sets the policy for discarding events when the buffer is full.
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        lock(&self.rx.0).overflow = overflow;
+    }
+
+    /// Gets the number of events that were discarded because the buffer
+    /// was full.
+    pub fn dropped(&self) -> u64 {
+        lock(&self.rx.0).dropped
+    }
+}
+
+impl<T> Stream for EventStream<'_, T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut shared = lock(&self.rx.0);
+        if let Some(evt) = shared.queue.pop_front() {
+            Poll::Ready(Some(evt))
+        }
+        else if shared.closed {
+            Poll::Ready(None)
+        }
+        else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let shared = lock(&self.rx.0);
+        let n = shared.queue.len();
+        (n, if shared.closed { Some(n) } else { None })
+    }
+}
+
+impl<T> Drop for EventStream<'_, T> {
+    fn drop(&mut self) {
+        if let Some(unregister) = self.unregister.take() {
+            unregister();
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, StreamExt};
+
+    // Collects the events from a stream after its sender is gone.
+    fn collect<T>(stream: EventStream<'_, T>) -> Vec<T> {
+        block_on(stream.collect())
+    }
+
+    #[test]
+    fn drop_newest_when_full() {
+        let (tx, rx) = channel();
+        let mut stream = EventStream::new(rx, || {});
+        stream.set_capacity(3);
+
+        for i in 0..5 {
+            tx.send(i);
+        }
+        assert_eq!(stream.dropped(), 2);
+
+        drop(tx);
+        assert_eq!(collect(stream), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn drop_oldest_when_full() {
+        let (tx, rx) = channel();
+        let mut stream = EventStream::new(rx, || {});
+        stream.set_capacity(3);
+        stream.set_overflow(Overflow::DropOldest);
+
+        for i in 0..5 {
+            tx.send(i);
+        }
+        assert_eq!(stream.dropped(), 2);
+
+        drop(tx);
+        assert_eq!(collect(stream), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn capacity_is_at_least_one() {
+        let (_tx, rx) = channel::<i32>();
+        let mut stream = EventStream::new(rx, || {});
+        assert_eq!(stream.capacity(), EVENT_BUFFER_SIZE);
+        stream.set_capacity(0);
+        assert_eq!(stream.capacity(), 1);
+    }
+
+    #[test]
+    fn unregister_on_drop() {
+        let (_tx, rx) = channel::<i32>();
+        let flag = Arc::new(Mutex::new(false));
+        let stream = EventStream::new(rx, {
+            let flag = Arc::clone(&flag);
+            move || *flag.lock().unwrap() = true
+        });
+        drop(stream);
+        assert!(*flag.lock().unwrap());
+    }
+}